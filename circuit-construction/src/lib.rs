@@ -913,6 +913,7 @@ where
         index,
         vec![],
         Some(blinders),
+        &[],
     )
     .unwrap()
 }
@@ -0,0 +1,38 @@
+use ark_ff::UniformRand;
+use ark_poly::{univariate::DensePolynomial, UVPolynomial};
+use criterion::{black_box, criterion_group, criterion_main, Criterion, SamplingMode};
+use kimchi::circuits::wires::{COLUMNS, PERMUTS};
+use mina_curves::pasta::fp::Fp;
+use o1_utils::ExtendedDensePolynomial;
+use rand::{rngs::StdRng, SeedableRng};
+use rayon::prelude::*;
+
+/// Compares evaluating the polynomials included in a proof
+/// (the first `PERMUTS - 1` sigmas, the witness columns and z) of a 2^16 circuit
+/// at two points one after the other, and in parallel as the prover does.
+pub fn bench_chunked_evaluations(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Chunked evaluations");
+    group.sample_size(10).sampling_mode(SamplingMode::Flat); // for slow benchmarks
+
+    let rng = &mut StdRng::from_seed([0u8; 32]);
+    let n = 1 << 16;
+    let polys: Vec<_> = (0..PERMUTS - 1 + COLUMNS + 1)
+        .map(|_| DensePolynomial::<Fp>::rand(n - 1, rng))
+        .collect();
+    let points = [Fp::rand(rng), Fp::rand(rng)];
+
+    let evaluate = |poly: &DensePolynomial<Fp>| {
+        let chunked = poly.to_chunked_polynomial(n);
+        points.map(|pt| chunked.evaluate_chunks(pt))
+    };
+
+    group.bench_function("sequential (2^16)", |b| {
+        b.iter(|| black_box(polys.iter().map(evaluate).collect::<Vec<_>>()))
+    });
+    group.bench_function("parallel (2^16)", |b| {
+        b.iter(|| black_box(polys.par_iter().map(evaluate).collect::<Vec<_>>()))
+    });
+}
+
+criterion_group!(benches, bench_chunked_evaluations);
+criterion_main!(benches);
@@ -0,0 +1,68 @@
+use ark_ff::UniformRand;
+use ark_poly::{univariate::DensePolynomial, UVPolynomial};
+use array_init::array_init;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, SamplingMode};
+use kimchi::circuits::{
+    constraints::ConstraintSystem,
+    expr::Constants,
+    gate::CircuitGate,
+    polynomials::generic::GenericGateSpec,
+    polynomials::permutation::ZK_ROWS,
+    wires::{Wire, COLUMNS},
+};
+use mina_curves::pasta::fp::Fp;
+use rand::{rngs::StdRng, SeedableRng};
+
+/// Compares evaluating the constraints of all the gates of a 2^12 circuit
+/// with the tree of boxed nodes ([kimchi::circuits::expr::Expr::evaluations]),
+/// and with its flattened form ([kimchi::circuits::expr::FlatExpr::evaluations]).
+pub fn bench_flat_expr(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Expression evaluation");
+    group.sample_size(10).sampling_mode(SamplingMode::Flat); // for slow benchmarks
+
+    let rng = &mut StdRng::from_seed([0u8; 32]);
+    let gates = (0..(1 << 12) - ZK_ROWS as usize)
+        .map(|row| {
+            CircuitGate::create_generic_gadget(
+                Wire::new(row),
+                GenericGateSpec::Const(1u32.into()),
+                None,
+            )
+        })
+        .collect();
+    let cs = ConstraintSystem::<Fp>::create(gates, oracle::pasta::fp_kimchi::params())
+        .build()
+        .unwrap();
+
+    let n = cs.domain.d1.size();
+    let witness: [_; COLUMNS] = array_init(|_| DensePolynomial::rand(n - 1, rng));
+    let z = DensePolynomial::rand(n - 1, rng);
+    let domain_evals = cs.evaluate(&witness, &z);
+    let constants = Constants {
+        alpha: Fp::rand(rng),
+        beta: Fp::rand(rng),
+        gamma: Fp::rand(rng),
+        joint_combiner: None,
+        endo_coefficient: cs.endo,
+        mds: cs.fr_sponge_params.mds.clone(),
+    };
+    let env = cs.fixed_environment().with_witness(
+        &domain_evals.d8.this.w,
+        &domain_evals.d8.this.z,
+        constants,
+        None,
+    );
+
+    let expr = cs.full_constraint(0).evaluate_constants(&env);
+    let flat = expr.flatten();
+
+    group.bench_function("tree (2^12)", |b| {
+        b.iter(|| black_box(expr.evaluations(&env)))
+    });
+    group.bench_function("flat (2^12)", |b| {
+        b.iter(|| black_box(flat.evaluations(&env)))
+    });
+}
+
+criterion_group!(benches, bench_flat_expr);
+criterion_main!(benches);
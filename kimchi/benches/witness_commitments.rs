@@ -0,0 +1,43 @@
+use ark_ff::UniformRand;
+use ark_poly::{EvaluationDomain, Evaluations, Radix2EvaluationDomain as D};
+use commitment_dlog::srs::SRS;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, SamplingMode};
+use kimchi::circuits::wires::COLUMNS;
+use mina_curves::pasta::{fp::Fp, vesta::Affine};
+use rand::{rngs::StdRng, SeedableRng};
+
+/// Compares committing to the witness columns of a 2^16 circuit one after the other,
+/// and with [SRS::commit_evaluations_non_hiding_batch] as the prover does.
+pub fn bench_witness_commitments(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Witness commitments");
+    group.sample_size(10).sampling_mode(SamplingMode::Flat); // for slow benchmarks
+
+    let rng = &mut StdRng::from_seed([0u8; 32]);
+    let domain = D::<Fp>::new(1 << 16).unwrap();
+    let mut srs = SRS::<Affine>::create(domain.size());
+    srs.add_lagrange_basis(domain);
+
+    let witness: Vec<_> = (0..COLUMNS)
+        .map(|_| {
+            let evals = (0..domain.size()).map(|_| Fp::rand(rng)).collect();
+            Evaluations::from_vec_and_domain(evals, domain)
+        })
+        .collect();
+
+    group.bench_function("per column (2^16)", |b| {
+        b.iter(|| {
+            black_box(
+                witness
+                    .iter()
+                    .map(|w| srs.commit_evaluations_non_hiding(domain, w, None))
+                    .collect::<Vec<_>>(),
+            )
+        })
+    });
+    group.bench_function("batch (2^16)", |b| {
+        b.iter(|| black_box(srs.commit_evaluations_non_hiding_batch(domain, &witness, None)))
+    });
+}
+
+criterion_group!(benches, bench_witness_commitments);
+criterion_main!(benches);
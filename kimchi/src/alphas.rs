@@ -21,6 +21,7 @@
 //!
 
 use crate::circuits::{argument::ArgumentType, gate::GateType};
+use crate::error::AlphasError;
 use ark_ff::Field;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -125,6 +126,27 @@ impl<F: Field> Alphas<F> {
         self.alphas = Some(alphas);
     }
 
+    /// Same as [Self::instantiate], but reuses the precomputed powers `powers` of `alpha`
+    /// instead of computing them.
+    /// Returns an error if `powers` does not start with `1, alpha`,
+    /// or has less powers than were registered.
+    pub fn instantiate_with_powers(&mut self, alpha: F, powers: &[F]) -> Result<(), AlphasError> {
+        let num = self.next_power as usize;
+        if powers.len() < num {
+            return Err(AlphasError::TooFewPowers {
+                registered: num,
+                given: powers.len(),
+            });
+        }
+        if powers.first().map_or(false, |p| !p.is_one())
+            || powers.get(1).map_or(false, |p| *p != alpha)
+        {
+            return Err(AlphasError::NotPowersOfAlpha);
+        }
+        self.alphas = Some(powers[..num].to_vec());
+        Ok(())
+    }
+
     /// This function allows us to retrieve the powers of alpha, upperbounded by `num`
     pub fn get_alphas(
         &self,
@@ -311,6 +333,34 @@ mod tests {
         assert_eq!(alphas.next(), Some(8.into()));
     }
 
+    #[test]
+    fn instantiate_with_powers() {
+        let mut alphas = Alphas::<Fp>::default();
+        alphas.register(ArgumentType::Gate(GateType::Poseidon), 4);
+        let alpha = Fp::from(3);
+
+        let mut expected = alphas.clone();
+        expected.instantiate(alpha);
+        let powers: Vec<_> = (0..6).map(|i| alpha.pow(&[i])).collect();
+        alphas.instantiate_with_powers(alpha, &powers).unwrap();
+        assert_eq!(alphas.alphas, expected.alphas);
+
+        let mut alphas = Alphas::<Fp>::default();
+        alphas.register(ArgumentType::Gate(GateType::Poseidon), 4);
+        assert!(matches!(
+            alphas.instantiate_with_powers(alpha, &powers[..3]),
+            Err(AlphasError::TooFewPowers {
+                registered: 4,
+                given: 3
+            })
+        ));
+        assert!(matches!(
+            alphas.instantiate_with_powers(Fp::from(2), &powers),
+            Err(AlphasError::NotPowersOfAlpha)
+        ));
+        assert!(alphas.alphas.is_none());
+    }
+
     // useful for the spec
 
     use crate::{
@@ -65,8 +65,9 @@ impl BenchmarkCtx {
         // group map
         let group_map = <Affine as CommitmentCurve>::Map::setup();
 
-        // create the index
+        // create the index, and compute the digest of its verifier index outside of the benchmarks
         let index = new_index_for_test(gates, 0);
+        index.verifier_index_digest::<BaseSponge>();
 
         // create the verifier index
         let verifier_index = index.verifier_index();
@@ -107,6 +108,7 @@ impl BenchmarkCtx {
             &self.index,
             vec![prev],
             None,
+            &[],
         )
         .unwrap()
     }
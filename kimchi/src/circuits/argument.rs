@@ -4,8 +4,10 @@
 //! Gates can be seen as filtered arguments,
 //! which apply only in some points (rows) of the domain.
 
+use std::collections::HashMap;
+
 use crate::{alphas::Alphas, circuits::expr::prologue::*};
-use ark_ff::FftField;
+use ark_ff::{FftField, Zero};
 use serde::{Deserialize, Serialize};
 
 use super::gate::GateType;
@@ -53,3 +55,144 @@ pub trait Argument<F: FftField> {
         }
     }
 }
+
+/// A registry of the constraints enforced by each gate,
+/// so that a gate's constraints can be obtained from a single place.
+#[derive(Clone, Debug)]
+pub struct ConstraintRegistry<F> {
+    gates: HashMap<GateType, Vec<E<F>>>,
+}
+
+impl<F> Default for ConstraintRegistry<F> {
+    fn default() -> Self {
+        Self {
+            gates: HashMap::new(),
+        }
+    }
+}
+
+impl<F: FftField> ConstraintRegistry<F> {
+    /// Registers the constraints of a gate.
+    /// This function will panic if you register the same gate twice.
+    pub fn register(&mut self, gate: GateType, constraints: Vec<E<F>>) {
+        if self.gates.insert(gate, constraints).is_some() {
+            panic!("cannot re-register the constraints of {:?}", gate);
+        }
+    }
+
+    /// Registers the constraints of a gate [Argument].
+    pub fn register_argument<A: Argument<F>>(&mut self) {
+        match A::ARGUMENT_TYPE {
+            ArgumentType::Gate(gate) => self.register(gate, A::constraints()),
+            ty => panic!("{:?} is not a gate", ty),
+        }
+    }
+
+    /// Returns the constraints registered for `gate`, if any.
+    pub fn get(&self, gate: GateType) -> Option<&[E<F>]> {
+        self.gates.get(&gate).map(Vec::as_slice)
+    }
+
+    /// The largest number of constraints of a registered gate,
+    /// which is the number of powers of alpha that the gates need.
+    pub fn max_constraints(&self) -> usize {
+        self.gates.values().map(Vec::len).max().unwrap_or(0)
+    }
+
+    /// The gates that have registered constraints, sorted.
+    pub fn gates(&self) -> Vec<GateType> {
+        // HashMap deliberately uses an unstable order; here we sort to obtain the same order every time.
+        let mut gates: Vec<_> = self.gates.keys().copied().collect();
+        gates.sort();
+        gates
+    }
+
+    /// Combines the constraints of `gate` with the powers of alpha registered for the gates in `alphas`,
+    /// filtered by the selector of the gate (see [Argument::combined_constraints]).
+    /// Returns `None` if no constraints were registered for `gate`.
+    pub fn combined_constraints(&self, gate: GateType, alphas: &Alphas<F>) -> Option<E<F>> {
+        let constraints = self.gates.get(&gate)?;
+        let num = u32::try_from(constraints.len())
+            .expect("we always expect a relatively low amount of constraints");
+        let alphas = alphas.get_exponents(ArgumentType::Gate(gate), num);
+        Some(index(gate) * E::combine_constraints(alphas, constraints.clone()))
+    }
+
+    /// Combines the constraints of every registered gate with the powers of alpha registered for the gates in `alphas`,
+    /// each filtered by the selector of its gate.
+    /// As gates are mutually exclusive, they all reuse the same powers of alpha.
+    pub fn all_combined_constraints(&self, alphas: &Alphas<F>) -> E<F> {
+        self.gates()
+            .into_iter()
+            .filter_map(|gate| self.combined_constraints(gate, alphas))
+            .fold(E::zero(), |acc, x| acc + x)
+    }
+
+    /// Same as [Self::all_combined_constraints], with the powers of alpha starting at `alpha0`.
+    pub fn combined(&self, alpha0: u32) -> E<F> {
+        self.gates()
+            .into_iter()
+            .map(|gate| {
+                let constraints = &self.gates[&gate];
+                let num = u32::try_from(constraints.len())
+                    .expect("we always expect a relatively low amount of constraints");
+                let alphas = alpha0..alpha0 + num;
+                index(gate) * E::combine_constraints(alphas, constraints.clone())
+            })
+            .fold(E::zero(), |acc, x| acc + x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuits::polynomials::{complete_add::CompleteAdd, poseidon::Poseidon};
+    use mina_curves::pasta::fp::Fp;
+
+    #[test]
+    fn test_constraint_registry() {
+        let mut registry = ConstraintRegistry::<Fp>::default();
+        registry.register_argument::<CompleteAdd<Fp>>();
+        registry.register_argument::<Poseidon<Fp>>();
+
+        assert_eq!(
+            registry.get(GateType::CompleteAdd).unwrap(),
+            CompleteAdd::<Fp>::constraints().as_slice()
+        );
+        assert!(registry.get(GateType::VarBaseMul).is_none());
+        assert_eq!(
+            registry.max_constraints(),
+            Poseidon::<Fp>::CONSTRAINTS as usize
+        );
+
+        // the registry combines the constraints like the arguments do
+        let mut alphas = Alphas::<Fp>::default();
+        alphas.register(
+            ArgumentType::Gate(GateType::Zero),
+            registry.max_constraints() as u32,
+        );
+        assert_eq!(
+            registry.combined(0),
+            Poseidon::combined_constraints(&alphas) + CompleteAdd::combined_constraints(&alphas)
+        );
+        assert_eq!(
+            registry.all_combined_constraints(&alphas),
+            registry.combined(0)
+        );
+        assert_eq!(
+            registry.combined_constraints(GateType::CompleteAdd, &alphas),
+            Some(CompleteAdd::combined_constraints(&alphas))
+        );
+        assert!(registry
+            .combined_constraints(GateType::VarBaseMul, &alphas)
+            .is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_constraint_registry_register_twice() {
+        let mut registry = ConstraintRegistry::<Fp>::default();
+        registry.register_argument::<CompleteAdd<Fp>>();
+        registry.register_argument::<CompleteAdd<Fp>>();
+    }
+}
@@ -1,16 +1,19 @@
 //! This module implements Plonk circuit constraint primitive.
 use crate::{
     circuits::{
+        argument::ConstraintRegistry,
         domain_constant_evaluation::DomainConstantEvaluations,
         domains::EvaluationDomains,
+        expr::{l0_1, ConstantExpr, Expr, FixedEnvironment},
         gate::{CircuitGate, GateType},
-        lookup::{index::LookupConstraintSystem, tables::LookupTable},
+        lookup::{self, index::LookupConstraintSystem, tables::LookupTable},
         polynomial::{WitnessEvals, WitnessOverDomains, WitnessShifts},
-        polynomials::permutation::{Shifts, ZK_ROWS},
+        polynomials::permutation::{zero_knowledge_rows, Shifts, ZK_ROWS},
         polynomials::range_check,
         wires::*,
     },
     error::SetupError,
+    linearization::gate_constraints,
 };
 use ark_ff::{FftField, SquareRootField, Zero};
 use ark_poly::{
@@ -20,11 +23,12 @@ use ark_poly::{
 use array_init::array_init;
 use o1_utils::ExtendedEvaluations;
 use once_cell::sync::OnceCell;
-use oracle::poseidon::ArithmeticSpongeParams;
+use oracle::{poseidon::ArithmeticSpongeParams, TranscriptVersion};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_with::serde_as;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use strum::IntoEnumIterator;
 
 use super::lookup::runtime_tables::RuntimeTableCfg;
 
@@ -130,11 +134,65 @@ pub struct ConstraintSystem<F: FftField> {
     #[serde(bound = "LookupConstraintSystem<F>: Serialize + DeserializeOwned")]
     pub lookup_constraint_system: Option<LookupConstraintSystem<F>>,
 
+    /// whether the prover randomizes the last `ZK_ROWS` rows of the witness
+    /// and of the other polynomials it computes.
+    /// **Warning:** if `false`, proofs are not zero-knowledge.
+    #[serde(default = "zero_knowledge_default")]
+    pub zero_knowledge: bool,
+
+    /// how the prover absorbs group elements into the Fq-Sponge,
+    /// the verifier index must use the same version
+    #[serde(default)]
+    pub transcript_version: TranscriptVersion,
+
     /// precomputes
     #[serde(skip)]
     precomputations: OnceCell<Arc<DomainConstantEvaluations<F>>>,
 }
 
+/// The fixed polynomials of a circuit, as exported by [ConstraintSystem::export_fixed_polynomials],
+/// in evaluation form over the domain `d1`.
+/// Committing to them with the Lagrange basis of `d1` gives the commitments of the
+/// [VerifierIndex](crate::verifier_index::VerifierIndex) of the circuit,
+/// so that it can be checked against the expected circuit.
+/// The polynomials of the lookup argument are not part of it.
+#[serde_as]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct FixedPolynomials<F: FftField> {
+    /// size of the domain `d1`
+    pub domain_size: u64,
+    /// permutation polynomials
+    #[serde_as(as = "Vec<Vec<o1_utils::serialization::SerdeAs>>")]
+    pub sigma: Vec<Vec<F>>,
+    /// coefficient polynomials
+    #[serde_as(as = "Vec<Vec<o1_utils::serialization::SerdeAs>>")]
+    pub coefficients: Vec<Vec<F>>,
+    /// generic gate selector
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub generic: Vec<F>,
+    /// poseidon gate selector
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub poseidon: Vec<F>,
+    /// EC addition gate selector
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub complete_add: Vec<F>,
+    /// EC variable base scalar multiplication gate selector
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub var_base_mul: Vec<F>,
+    /// endoscalar multiplication gate selector
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub endo_mul: Vec<F>,
+    /// endoscalar multiplication scalar computation gate selector
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub endo_mul_scalar: Vec<F>,
+    /// ChaCha gate selectors, if the circuit uses them
+    #[serde_as(as = "Option<Vec<Vec<o1_utils::serialization::SerdeAs>>>")]
+    pub chacha: Option<Vec<Vec<F>>>,
+    /// range check gate selectors
+    #[serde_as(as = "Vec<Vec<o1_utils::serialization::SerdeAs>>")]
+    pub range_check: Vec<Vec<F>>,
+}
+
 /// Represents an error found when verifying a witness with a gate
 #[derive(Debug)]
 pub enum GateError {
@@ -144,6 +202,11 @@ pub enum GateError {
     IncorrectPublic(usize),
     /// A specific gate did not verify correctly
     Custom { row: usize, err: String },
+    /// The witness has the given number of rows, which is more than the size of the domain
+    WitnessTooLarge(usize),
+    /// The witness has the given number of rows, which reaches into
+    /// the last [zero_knowledge_rows] rows of the domain while zero-knowledge is enabled
+    WitnessInZkRows(usize),
 }
 
 pub struct Builder<F: FftField> {
@@ -153,6 +216,12 @@ pub struct Builder<F: FftField> {
     lookup_tables: Vec<LookupTable<F>>,
     runtime_tables: Option<Vec<RuntimeTableCfg<F>>>,
     precomputations: Option<Arc<DomainConstantEvaluations<F>>>,
+    zero_knowledge: bool,
+    transcript_version: TranscriptVersion,
+}
+
+fn zero_knowledge_default() -> bool {
+    true
 }
 
 impl<F: FftField + SquareRootField> ConstraintSystem<F> {
@@ -163,6 +232,8 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
     /// - `lookup_tables: vec![]`,
     /// - `runtime_tables: None`,
     /// - `precomputations: None`,
+    /// - `zero_knowledge: true`,
+    /// - `transcript_version: TranscriptVersion::V0`,
     ///
     /// How to use it:
     /// 1. Create your instance of your builder for the constraint system using `crate(gates, sponge params)`
@@ -179,6 +250,8 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
             lookup_tables: vec![],
             runtime_tables: None,
             precomputations: None,
+            zero_knowledge: true,
+            transcript_version: TranscriptVersion::default(),
         }
     }
 
@@ -193,11 +266,101 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
             .expect("Precomputation has been set before");
     }
 
+    /// Returns the part of the expression [Environment](crate::circuits::expr::Environment)
+    /// that only depends on the circuit, and can be shared by all the proofs.
+    pub fn fixed_environment(&self) -> FixedEnvironment<'_, F> {
+        let mut index = HashMap::new();
+        use GateType::*;
+        index.insert(Generic, &self.generic4);
+        index.insert(Poseidon, &self.ps8);
+        index.insert(CompleteAdd, &self.complete_addl4);
+        index.insert(VarBaseMul, &self.mull8);
+        index.insert(EndoMul, &self.emull);
+        index.insert(EndoMulScalar, &self.endomul_scalar8);
+        if let Some(c) = &self.chacha8 {
+            index.extend([ChaCha0, ChaCha1, ChaCha2, ChaChaFinal].into_iter().zip(c));
+        }
+        if !self.range_check_selector_polys.is_empty() {
+            index.extend(
+                range_check::circuit_gates()
+                    .iter()
+                    .zip(&self.range_check_selector_polys)
+                    .map(|(gate_type, selector)| (*gate_type, &selector.eval8)),
+            );
+        }
+
+        FixedEnvironment {
+            coefficient: &self.coefficients8,
+            vanishes_on_last_4_rows: &self.precomputations().vanishes_on_last_4_rows,
+            index,
+            l0_1: l0_1(self.domain.d1),
+            domain: self.domain,
+            column_names: HashMap::new(),
+        }
+    }
+
+    /// Returns the constraints of all the gates used by the circuit as a single expression,
+    /// combined with the powers of alpha starting at `alpha0` (see [ConstraintRegistry::combined]).
+    /// The permutation and the lookup arguments are not part of it.
+    pub fn full_constraint(&self, alpha0: u32) -> Expr<ConstantExpr<F>> {
+        self.constraint_registry().combined(alpha0)
+    }
+
+    /// Registers the constraints of all the gates used by the circuit,
+    /// as the linearization does (see [gate_constraints]).
+    pub(crate) fn constraint_registry(&self) -> ConstraintRegistry<F> {
+        gate_constraints(
+            self.chacha8.is_some(),
+            !self.range_check_selector_polys.is_empty(),
+        )
+    }
+
+    /// Exports the fixed polynomials of the circuit, see [FixedPolynomials].
+    pub fn export_fixed_polynomials(&self) -> FixedPolynomials<F> {
+        let d1 = self.domain.d1;
+        // the evaluations over d1 are a subset of the evaluations over a larger domain
+        let evals_d1 = |e: &E<F, D<F>>| -> Vec<F> {
+            let step = e.evals.len() / d1.size();
+            e.evals.iter().step_by(step).copied().collect()
+        };
+
+        FixedPolynomials {
+            domain_size: d1.size,
+            sigma: self.sigmal1.iter().map(|e| e.evals.clone()).collect(),
+            coefficients: self.coefficients8.iter().map(evals_d1).collect(),
+            generic: evals_d1(&self.generic4),
+            poseidon: evals_d1(&self.ps8),
+            complete_add: evals_d1(&self.complete_addl4),
+            var_base_mul: evals_d1(&self.mull8),
+            endo_mul: evals_d1(&self.emull),
+            endo_mul_scalar: evals_d1(&self.endomul_scalar8),
+            chacha: self
+                .chacha8
+                .as_ref()
+                .map(|c| c.iter().map(evals_d1).collect()),
+            range_check: self
+                .range_check_selector_polys
+                .iter()
+                .map(|poly| evals_d1(&poly.eval8))
+                .collect(),
+        }
+    }
+
     /// This function verifies the consistency of the wire
     /// assignements (witness) against the constraints
     ///     witness: wire assignement witness
     ///     RETURN: verification status
     pub fn verify(&self, witness: &[Vec<F>; COLUMNS], public: &[F]) -> Result<(), GateError> {
+        let length_witness = witness[0].len();
+        if length_witness > self.domain.d1.size() {
+            return Err(GateError::WitnessTooLarge(length_witness));
+        }
+
+        // the last rows are overwritten by the prover to achieve zero-knowledge
+        if self.zero_knowledge && length_witness > self.domain.d1.size() - zero_knowledge_rows() {
+            return Err(GateError::WitnessInZkRows(length_witness));
+        }
+
         // pad the witness
         let pad = vec![F::zero(); self.domain.d1.size() - witness[0].len()];
         let witness: [Vec<F>; COLUMNS] = array_init(|i| {
@@ -287,6 +450,79 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
     }
 }
 
+/// A difference between the constraints enforced by two constraint systems, see [constraint_diff].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConstraintDiff {
+    /// The systems have a different number of public inputs.
+    Public(usize, usize),
+    /// The constraints of this gate type differ.
+    Constraints(GateType),
+    /// The lookup constraints differ.
+    Lookup,
+    /// The gates at this row differ in type, wiring or coefficients,
+    /// or only one of the systems has a gate at this row.
+    Gate(usize),
+}
+
+/// Returns the differences between the constraints enforced by `a` and `b`,
+/// comparing their public inputs, the constraints of each gate type and of the lookup argument,
+/// and their gates row by row.
+/// Constraints are compared after [Expr::canonicalize],
+/// so constraints that only differ in the order of the operands of `+` and `*` are considered the same.
+/// Constraints that are equal as polynomials but written differently are reported as a difference.
+pub fn constraint_diff<F: FftField + SquareRootField>(
+    a: &ConstraintSystem<F>,
+    b: &ConstraintSystem<F>,
+) -> Vec<ConstraintDiff> {
+    let mut diff = vec![];
+
+    if a.public != b.public {
+        diff.push(ConstraintDiff::Public(a.public, b.public));
+    }
+
+    let canonical = |constraints: &[Expr<ConstantExpr<F>>]| -> Vec<_> {
+        constraints.iter().map(Expr::canonicalize).collect()
+    };
+
+    let (registry_a, registry_b) = (a.constraint_registry(), b.constraint_registry());
+    for gate in GateType::iter() {
+        if registry_a.get(gate).map(canonical) != registry_b.get(gate).map(canonical) {
+            diff.push(ConstraintDiff::Constraints(gate));
+        }
+    }
+
+    let lookup_constraints = |cs: &ConstraintSystem<F>| {
+        cs.lookup_constraint_system
+            .as_ref()
+            .map(|lcs| canonical(&lookup::constraints::constraints(&lcs.configuration)))
+    };
+    if lookup_constraints(a) != lookup_constraints(b) {
+        diff.push(ConstraintDiff::Lookup);
+    }
+
+    for row in 0..std::cmp::max(a.gates.len(), b.gates.len()) {
+        let same = match (a.gates.get(row), b.gates.get(row)) {
+            (Some(ga), Some(gb)) => {
+                ga.typ == gb.typ && ga.wires == gb.wires && ga.coeffs == gb.coeffs
+            }
+            _ => false,
+        };
+        if !same {
+            diff.push(ConstraintDiff::Gate(row));
+        }
+    }
+
+    diff
+}
+
+/// Returns true if `a` and `b` enforce the same constraints, see [constraint_diff].
+pub fn constraints_equivalent<F: FftField + SquareRootField>(
+    a: &ConstraintSystem<F>,
+    b: &ConstraintSystem<F>,
+) -> bool {
+    constraint_diff(a, b).is_empty()
+}
+
 impl<F: FftField + SquareRootField> Builder<F> {
     /// Set up the number of public inputs.
     /// If not invoked, it equals `0` by default.
@@ -327,6 +563,33 @@ impl<F: FftField + SquareRootField> Builder<F> {
         self
     }
 
+    /// Disable zero-knowledge.
+    /// If not invoked, zero-knowledge is enabled by default.
+    ///
+    /// Without zero-knowledge, the prover leaves the last `ZK_ROWS` rows of the witness,
+    /// of the permutation polynomial and of the lookup polynomials deterministic
+    /// instead of randomizing them.
+    /// The circuit layout, the constraints and the proofs are unchanged,
+    /// so the verifier does not need to know about it.
+    /// This is useful to measure the cost of zero-knowledge,
+    /// but **the resulting proofs can leak information about the witness**.
+    pub fn disable_zero_knowledge(mut self) -> Self {
+        self.zero_knowledge = false;
+        self
+    }
+
+    /// Set the version of the transcript, that is how commitments are absorbed into the Fq-Sponge.
+    /// If not invoked, it is `TranscriptVersion::V0`, which absorbs both coordinates of each point.
+    ///
+    /// `TranscriptVersion::V1` absorbs points in compressed form,
+    /// which makes the sponge cheaper but yields different challenges:
+    /// proofs created with one version do not verify with the other.
+    /// The verifier index inherits the version of the constraint system.
+    pub fn transcript_version(mut self, transcript_version: TranscriptVersion) -> Self {
+        self.transcript_version = transcript_version;
+        self
+    }
+
     /// Build the [ConstraintSystem] from a [Builder].
     pub fn build(self) -> Result<ConstraintSystem<F>, SetupError> {
         let mut gates = self.gates;
@@ -562,6 +825,8 @@ impl<F: FftField + SquareRootField> Builder<F> {
             endo,
             fr_sponge_params: self.sponge_params,
             lookup_constraint_system,
+            zero_knowledge: self.zero_knowledge,
+            transcript_version: self.transcript_version,
             precomputations: domain_constant_evaluation,
         };
 
@@ -580,7 +845,18 @@ impl<F: FftField + SquareRootField> Builder<F> {
 #[cfg(test)]
 pub mod tests {
     use super::*;
-    use ark_ff::{FftField, SquareRootField};
+    use crate::alphas::Alphas;
+    use crate::circuits::argument::{Argument, ArgumentType};
+    use crate::circuits::lookup::runtime_tables::RuntimeTableSpec;
+    use crate::circuits::polynomials::{
+        complete_add::CompleteAdd,
+        endomul_scalar::EndomulScalar,
+        endosclmul::EndosclMul,
+        generic::{Generic, GenericGateSpec},
+        poseidon::Poseidon,
+        varbasemul::VarbaseMul,
+    };
+    use ark_ff::{FftField, One, SquareRootField};
     use mina_curves::pasta::fp::Fp;
 
     impl<F: FftField + SquareRootField> ConstraintSystem<F> {
@@ -603,4 +879,197 @@ pub mod tests {
             Self::for_testing(fp_sponge_params, gates)
         }
     }
+
+    #[test]
+    fn test_full_constraint() {
+        let gates = (0..2)
+            .map(|row| {
+                CircuitGate::create_generic_gadget(
+                    Wire::new(row),
+                    GenericGateSpec::Const(Fp::from(1u32)),
+                    None,
+                )
+            })
+            .collect();
+        let cs = ConstraintSystem::fp_for_testing(gates);
+
+        let mut alphas = Alphas::<Fp>::default();
+        alphas.register(
+            ArgumentType::Gate(GateType::Zero),
+            VarbaseMul::<Fp>::CONSTRAINTS,
+        );
+        let expected = Generic::combined_constraints(&alphas)
+            + Poseidon::combined_constraints(&alphas)
+            + CompleteAdd::combined_constraints(&alphas)
+            + VarbaseMul::combined_constraints(&alphas)
+            + EndosclMul::combined_constraints(&alphas)
+            + EndomulScalar::combined_constraints(&alphas);
+        assert_eq!(cs.full_constraint(0), expected);
+    }
+
+    #[test]
+    fn test_constraint_diff() {
+        let gate = |row, c: u32| {
+            CircuitGate::create_generic_gadget(
+                Wire::new(row),
+                GenericGateSpec::Const(Fp::from(c)),
+                None,
+            )
+        };
+        let cs = ConstraintSystem::fp_for_testing(vec![gate(0, 1), gate(1, 2)]);
+
+        // the generic constraints are compared too
+        assert_eq!(
+            cs.constraint_registry().get(GateType::Generic),
+            Some(Generic::<Fp>::constraints().as_slice())
+        );
+
+        let same = ConstraintSystem::fp_for_testing(vec![gate(0, 1), gate(1, 2)]);
+        assert!(constraints_equivalent(&cs, &same));
+
+        let other_coeffs = ConstraintSystem::fp_for_testing(vec![gate(0, 1), gate(1, 3)]);
+        assert_eq!(
+            constraint_diff(&cs, &other_coeffs),
+            vec![ConstraintDiff::Gate(1)]
+        );
+
+        let longer = ConstraintSystem::fp_for_testing(vec![gate(0, 1), gate(1, 2), gate(2, 2)]);
+        assert!(!constraints_equivalent(&cs, &longer));
+        assert_eq!(constraint_diff(&cs, &longer), vec![ConstraintDiff::Gate(2)]);
+
+        let public = ConstraintSystem::create(
+            vec![gate(0, 1), gate(1, 2)],
+            oracle::pasta::fp_kimchi::params(),
+        )
+        .public(1)
+        .build()
+        .unwrap();
+        assert_eq!(
+            constraint_diff(&cs, &public),
+            vec![ConstraintDiff::Public(0, 1)]
+        );
+
+        // enabling the chacha gates adds their constraints and the lookup argument they use
+        let generic_gates: Vec<_> = (0..300).map(|row| gate(row, 1)).collect();
+        let mut chacha_gates = generic_gates.clone();
+        chacha_gates[299] = CircuitGate {
+            typ: GateType::ChaCha0,
+            wires: Wire::new(299),
+            coeffs: vec![],
+        };
+        let generic = ConstraintSystem::fp_for_testing(generic_gates);
+        let chacha = ConstraintSystem::fp_for_testing(chacha_gates);
+        assert_eq!(
+            constraint_diff(&generic, &chacha),
+            vec![
+                ConstraintDiff::Constraints(GateType::ChaCha0),
+                ConstraintDiff::Constraints(GateType::ChaCha1),
+                ConstraintDiff::Constraints(GateType::ChaCha2),
+                ConstraintDiff::Constraints(GateType::ChaChaFinal),
+                ConstraintDiff::Lookup,
+                ConstraintDiff::Gate(299),
+            ]
+        );
+
+        // runtime tables change the lookup constraints only
+        let lookup_gates: Vec<_> = (0..10)
+            .map(|row| CircuitGate {
+                typ: GateType::Lookup,
+                wires: Wire::new(row),
+                coeffs: vec![],
+            })
+            .collect();
+        let table = LookupTable {
+            id: 0,
+            data: vec![
+                vec![Fp::zero(), Fp::one()],
+                vec![Fp::from(5u32), Fp::from(6u32)],
+            ],
+        };
+        let lookup =
+            ConstraintSystem::create(lookup_gates.clone(), oracle::pasta::fp_kimchi::params())
+                .lookup(vec![table.clone()])
+                .build()
+                .unwrap();
+        let runtime = ConstraintSystem::create(lookup_gates, oracle::pasta::fp_kimchi::params())
+            .lookup(vec![table])
+            .runtime(Some(vec![RuntimeTableCfg::Indexed(RuntimeTableSpec {
+                id: 1,
+                len: 2,
+            })]))
+            .build()
+            .unwrap();
+        assert_eq!(
+            constraint_diff(&lookup, &runtime),
+            vec![ConstraintDiff::Lookup]
+        );
+    }
+
+    #[test]
+    fn test_verify_witness_in_zk_rows() {
+        let gates = (0..2)
+            .map(|row| {
+                CircuitGate::create_generic_gadget(
+                    Wire::new(row),
+                    GenericGateSpec::Const(Fp::from(1u32)),
+                    None,
+                )
+            })
+            .collect();
+        let cs = ConstraintSystem::fp_for_testing(gates);
+        let n = cs.domain.d1.size();
+
+        let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); 2]);
+        witness[0][0] = Fp::from(1u32);
+        witness[0][1] = Fp::from(1u32);
+        cs.verify(&witness, &[]).unwrap();
+
+        // the witness can be padded up to the zero-knowledge rows...
+        for w in &mut witness {
+            w.resize(n - zero_knowledge_rows(), Fp::zero());
+        }
+        cs.verify(&witness, &[]).unwrap();
+
+        // ...but not into them
+        for w in &mut witness {
+            w.push(Fp::zero());
+        }
+        assert!(matches!(
+            cs.verify(&witness, &[]),
+            Err(GateError::WitnessInZkRows(len)) if len == n - zero_knowledge_rows() + 1
+        ));
+    }
+
+    #[test]
+    fn test_verify_witness_in_zk_rows_without_zero_knowledge() {
+        let gates = (0..2)
+            .map(|row| {
+                CircuitGate::create_generic_gadget(
+                    Wire::new(row),
+                    GenericGateSpec::Const(Fp::from(1u32)),
+                    None,
+                )
+            })
+            .collect();
+        let cs = ConstraintSystem::create(gates, oracle::pasta::fp_kimchi::params())
+            .disable_zero_knowledge()
+            .build()
+            .unwrap();
+        let n = cs.domain.d1.size();
+
+        // without zero-knowledge, the witness can use the last rows of the domain...
+        let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); n]);
+        witness[0][0] = Fp::from(1u32);
+        witness[0][1] = Fp::from(1u32);
+        cs.verify(&witness, &[]).unwrap();
+
+        // ...but not go past the domain
+        for w in &mut witness {
+            w.push(Fp::zero());
+        }
+        assert!(matches!(
+            cs.verify(&witness, &[]),
+            Err(GateError::WitnessTooLarge(len)) if len == n + 1
+        ));
+    }
 }
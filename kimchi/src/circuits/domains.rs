@@ -3,6 +3,7 @@ use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as Domain};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
+use crate::circuits::polynomials::permutation::ZK_ROWS;
 use crate::error::SetupError;
 
 #[serde_as]
@@ -18,6 +19,36 @@ pub struct EvaluationDomains<F: FftField> {
     pub d8: Domain<F>, // size 8n
 }
 
+/// The size of the domain of a circuit, as returned by [EvaluationDomains::domain_size_for].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DomainSize {
+    /// the number of gates of the circuit
+    pub gates: usize,
+    /// the size of the domain `d1`
+    pub size: usize,
+    /// the log2 of `size`
+    pub log2: u32,
+    /// the number of rows that are neither gates nor zero-knowledge rows
+    pub padding: usize,
+}
+
+impl DomainSize {
+    /// Returns whether the circuit needs padding rows to fill its domain.
+    pub fn is_padded(&self) -> bool {
+        self.padding > 0
+    }
+}
+
+impl std::fmt::Display for DomainSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "your {}-gate circuit uses a {}-size domain ({} bits), {} rows of padding",
+            self.gates, self.size, self.log2, self.padding
+        )
+    }
+}
+
 impl<F: FftField> EvaluationDomains<F> {
     /// Creates 4 evaluation domains `d1` (of size `n`), `d2` (of size `2n`), `d4` (of size `4n`),
     /// and `d8` (of size `8n`). If generator of `d8` is `g`, the generator
@@ -51,14 +82,97 @@ impl<F: FftField> EvaluationDomains<F> {
 
         Ok(EvaluationDomains { d1, d2, d4, d8 })
     }
+
+    /// Returns the size of the domain `d1` of a circuit of `gates` gates, its log2,
+    /// and the number of padding rows.
+    /// The domain is the smallest power of two that fits the gates and the `ZK_ROWS`
+    /// zero-knowledge rows, so that a circuit of 1000 gates uses a domain of size 1024 (`2^10`),
+    /// leaving `1024 - 1000 - ZK_ROWS = 21` rows of padding.
+    pub fn domain_size_for(gates: usize) -> Result<DomainSize, SetupError> {
+        let size = Domain::<F>::compute_size_of_domain(gates + ZK_ROWS as usize).ok_or(
+            SetupError::DomainCreation("could not compute size of domain"),
+        )?;
+        Ok(DomainSize {
+            gates,
+            size,
+            log2: size.trailing_zeros(),
+            padding: size - gates - ZK_ROWS as usize,
+        })
+    }
+
+    /// Returns the two points at which the polynomials are evaluated, `(zeta, zeta * omega)`,
+    /// where `omega` is the generator of `d1` (and not of any of the larger domains).
+    pub fn evaluation_points(&self, zeta: F) -> (F, F) {
+        (zeta, zeta * self.d1.group_gen)
+    }
+
+    /// Returns a sensible `max_poly_size` (that is, the size of the SRS) for a circuit
+    /// over these domains, which is always the size of `d1`.
+    ///
+    /// Polynomials larger than `max_poly_size` are split in chunks, each committed and evaluated separately:
+    /// a smaller value makes the proof larger, while a larger value requires a larger SRS for no benefit.
+    /// Using the size of `d1`, every column fits in a single chunk,
+    /// and only the quotient polynomial is chunked.
+    /// The degree of the constraints only changes the degree of the quotient, and so its number of chunks,
+    /// which is why it does not change this value.
+    pub fn recommended_max_poly_size(&self) -> usize {
+        self.d1.size()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ark_ff::Field;
+    use ark_ff::{Field, One};
     use mina_curves::pasta::fp::Fp;
 
+    #[test]
+    fn test_evaluation_points() {
+        let d = EvaluationDomains::<Fp>::create(8).unwrap();
+
+        let (zeta, zeta_omega) = d.evaluation_points(Fp::one());
+        assert_eq!(zeta, Fp::one());
+        assert_eq!(zeta_omega, d.d1.group_gen);
+        assert_ne!(zeta_omega, d.d8.group_gen);
+        assert_eq!(zeta_omega.pow(&[8]), Fp::one());
+        assert_ne!(zeta_omega.pow(&[4]), Fp::one());
+
+        let zeta = Fp::from(42u32);
+        let (_, zeta_omega) = d.evaluation_points(zeta);
+        assert_eq!(zeta_omega, zeta * d.d8.group_gen.pow(&[8]));
+    }
+
+    #[test]
+    fn test_domain_size_for() {
+        let size = EvaluationDomains::<Fp>::domain_size_for(1000).unwrap();
+        assert_eq!((size.size, size.log2, size.padding), (1024, 10, 21));
+        assert!(size.is_padded());
+        assert_eq!(
+            size.to_string(),
+            "your 1000-gate circuit uses a 1024-size domain (10 bits), 21 rows of padding"
+        );
+
+        // no room for the zero-knowledge rows in a domain of size 1024
+        let size = EvaluationDomains::<Fp>::domain_size_for(1022).unwrap();
+        assert_eq!((size.size, size.log2, size.padding), (2048, 11, 1023));
+
+        // the gates and the zero-knowledge rows exactly fill the domain
+        let size = EvaluationDomains::<Fp>::domain_size_for(1021).unwrap();
+        assert_eq!((size.size, size.log2, size.padding), (1024, 10, 0));
+        assert!(!size.is_padded());
+
+        // it is the size of the domain used by the constraint system
+        let size = EvaluationDomains::<Fp>::domain_size_for(100).unwrap();
+        let d = EvaluationDomains::<Fp>::create(100 + ZK_ROWS as usize).unwrap();
+        assert_eq!(d.d1.size(), size.size);
+    }
+
+    #[test]
+    fn test_recommended_max_poly_size() {
+        let d = EvaluationDomains::<Fp>::create(100).unwrap();
+        assert_eq!(d.recommended_max_poly_size(), 128);
+    }
+
     #[test]
     #[ignore] // TODO(mimoo): wait for fix upstream (https://github.com/arkworks-rs/algebra/pull/307)
     fn test_create_domain() {
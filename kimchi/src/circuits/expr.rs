@@ -10,9 +10,12 @@ use crate::{
 };
 use ark_ff::{FftField, Field, One, PrimeField, Zero};
 use ark_poly::{
-    univariate::DensePolynomial, EvaluationDomain, Evaluations, Radix2EvaluationDomain as D,
+    univariate::DensePolynomial, EvaluationDomain, Evaluations, Polynomial,
+    Radix2EvaluationDomain as D, UVPolynomial,
 };
+use commitment_dlog::commitment::{CommitmentCurve, PolyComm};
 use itertools::Itertools;
+use num_bigint::BigUint;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::iter::FromIterator;
@@ -21,6 +24,7 @@ use std::{
     collections::{HashMap, HashSet},
     ops::MulAssign,
 };
+use strum::IntoEnumIterator;
 use thiserror::Error;
 use CurrOrNext::{Curr, Next};
 
@@ -29,20 +33,30 @@ pub enum ExprError {
     #[error("Empty stack")]
     EmptyStack,
 
-    #[error("Lookup should not have been used")]
-    LookupShouldNotBeUsed,
-
-    #[error("Linearization failed (needed {0:?} evaluated at the {1:?} row")]
+    #[error("Missing the evaluation of {0} at the {1:?} row")]
     MissingEvaluation(Column, CurrOrNext),
 
-    #[error("Cannot get index evaluation {0:?} (should have been linearized away)")]
+    #[error("Cannot get index evaluation {0} (should have been linearized away)")]
     MissingIndexEvaluation(Column),
 
     #[error("Linearization failed")]
     FailedLinearization,
 
-    #[error("runtime table not available")]
-    MissingRuntime,
+    #[error("parameter {0} is not bound")]
+    UnboundParameter(String),
+
+    #[error("no commitment was given for index column {0}")]
+    MissingCommitment(Column),
+
+    #[error("the evaluations of {0} have size {1}, but their domain has size {2}")]
+    WrongEvaluationsSize(String, usize, usize),
+
+    /// The column, and its name in the environment
+    #[error("the column {1} is not in the environment")]
+    MissingColumn(Column, String),
+
+    #[error("the exponent {0} is larger than the maximum {1}")]
+    PowerTooLarge(u64, u64),
 }
 
 /// The collection of constants required to evaluate an `Expr`.
@@ -104,10 +118,68 @@ pub struct Environment<'a, F: FftField> {
     pub domain: EvaluationDomains<F>,
     /// Lookup specific polynomials
     pub lookup: Option<LookupEnvironment<'a, F>>,
+    /// Optional names of the witness columns, used in diagnostics.
+    /// Columns without a name are referred to by their index.
+    pub column_names: HashMap<usize, String>,
+}
+
+/// The part of an [Environment] that only depends on the circuit,
+/// and that can thus be reused for several proofs.
+/// The proof-specific part is given in [FixedEnvironment::with_witness].
+pub struct FixedEnvironment<'a, F: FftField> {
+    /// The coefficient column polynomials
+    pub coefficient: &'a [Evaluations<F, D<F>>; COLUMNS],
+    /// The polynomial which vanishes on the last 4 elements of the domain.
+    pub vanishes_on_last_4_rows: &'a Evaluations<F, D<F>>,
+    /// The index selector polynomials.
+    pub index: HashMap<GateType, &'a Evaluations<F, D<F>>>,
+    /// The value `prod_{j != 1} (1 - omega^j)`, see [Environment::l0_1].
+    pub l0_1: F,
+    /// The domains used in the PLONK argument.
+    pub domain: EvaluationDomains<F>,
+    /// Optional names of the witness columns, see [Environment::column_names].
+    pub column_names: HashMap<usize, String>,
+}
+
+impl<'a, F: FftField> FixedEnvironment<'a, F> {
+    /// Completes the environment with the evaluations and constants of a proof.
+    pub fn with_witness(
+        &self,
+        witness: &'a [Evaluations<F, D<F>>; COLUMNS],
+        z: &'a Evaluations<F, D<F>>,
+        constants: Constants<F>,
+        lookup: Option<LookupEnvironment<'a, F>>,
+    ) -> Environment<'a, F> {
+        Environment {
+            witness,
+            coefficient: self.coefficient,
+            vanishes_on_last_4_rows: self.vanishes_on_last_4_rows,
+            z,
+            index: self.index.clone(),
+            l0_1: self.l0_1,
+            constants,
+            domain: self.domain,
+            lookup,
+            column_names: self.column_names.clone(),
+        }
+    }
 }
 
 impl<'a, F: FftField> Environment<'a, F> {
-    fn get_column(&self, col: &Column) -> Option<&'a Evaluations<F, D<F>>> {
+    /// Returns the name of the column `col`, as registered in `column_names`.
+    pub fn column_name(&self, col: &Column) -> String {
+        col.text(&self.column_names)
+    }
+
+    /// Returns an iterator over the evaluations of the witness columns.
+    pub fn witness_columns(&self) -> impl Iterator<Item = &'a Evaluations<F, D<F>>> {
+        self.witness.iter()
+    }
+
+    /// Returns the evaluations backing the column `col`,
+    /// or `None` if the environment does not contain them
+    /// (for example, lookup columns when lookups are not used).
+    pub fn column(&self, col: &Column) -> Option<&'a Evaluations<F, D<F>>> {
         use Column::*;
         let lookup = self.lookup.as_ref();
         match col {
@@ -126,6 +198,56 @@ impl<'a, F: FftField> Environment<'a, F> {
             },
         }
     }
+
+    /// Checks that the evaluations of every column of the environment are over the domain
+    /// the expression framework expects for it
+    /// (`d8`, except for the `CompleteAdd` and `Generic` selectors over `d4`).
+    /// Evaluations over another domain are not detected during evaluation,
+    /// and silently lead to wrong results,
+    /// so it is worth calling this in debug builds before evaluating expressions.
+    pub fn validate(&self) -> Result<(), ExprError> {
+        let check = |name: String, evals: &Evaluations<F, D<F>>, d: D<F>| {
+            if evals.evals.len() == d.size() {
+                Ok(())
+            } else {
+                Err(ExprError::WrongEvaluationsSize(
+                    name,
+                    evals.evals.len(),
+                    d.size(),
+                ))
+            }
+        };
+
+        let num_sorted = self.lookup.as_ref().map_or(0, |l| l.sorted.len());
+        let columns = (0..COLUMNS)
+            .map(Column::Witness)
+            .chain((0..COLUMNS).map(Column::Coefficient))
+            .chain([
+                Column::Z,
+                Column::LookupAggreg,
+                Column::LookupTable,
+                Column::LookupRuntimeSelector,
+                Column::LookupRuntimeTable,
+            ])
+            .chain((0..num_sorted).map(Column::LookupSorted))
+            .chain(LookupPattern::iter().map(Column::LookupKindIndex))
+            .chain(self.index.keys().map(|t| Column::Index(*t)));
+        for col in columns {
+            if let Some(evals) = self.column(&col) {
+                check(
+                    self.column_name(&col),
+                    evals,
+                    get_domain(col.domain(), self),
+                )?;
+            }
+        }
+
+        check(
+            "vanishes_on_last_4_rows".to_string(),
+            self.vanishes_on_last_4_rows,
+            self.domain.d8,
+        )
+    }
 }
 
 // In this file, we define...
@@ -140,6 +262,9 @@ impl<'a, F: FftField> Environment<'a, F> {
 
 /// Computes `prod_{j != n} (1 - omega^j)`
 ///     Assure we don't multiply by (1 - omega^n) = (1 - omega^0) = (1 - 1) = 0
+///
+/// This is the value of `l_0` at `1`, which is always the size `n` of the domain.
+/// In particular, for a domain of size 1 the product is empty, and the result is `1`.
 pub fn l0_1<F: FftField>(d: D<F>) -> F {
     d.elements()
         .skip(1)
@@ -158,6 +283,7 @@ fn unnormalized_lagrange_basis<F: FftField>(domain: &D<F>, i: i32, pt: &F) -> F
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 /// A type representing one of the polynomials involved in the PLONK IOP.
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
 pub enum Column {
     Witness(usize),
     Z,
@@ -174,7 +300,7 @@ pub enum Column {
 impl Column {
     fn domain(&self) -> Domain {
         match self {
-            Column::Index(GateType::CompleteAdd) => Domain::D4,
+            Column::Index(GateType::CompleteAdd) | Column::Index(GateType::Generic) => Domain::D4,
             _ => Domain::D8,
         }
     }
@@ -195,11 +321,38 @@ impl Column {
             Column::Coefficient(i) => format!("c_{{{}}}", i),
         }
     }
+
+    /// Renders the column as plain text.
+    /// Witness columns are rendered as their name in `names` if there is one,
+    /// and as `w3` (for the column 3) otherwise.
+    pub fn text(&self, names: &HashMap<usize, String>) -> String {
+        match self {
+            Column::Witness(i) => names.get(i).cloned().unwrap_or_else(|| format!("w{i}")),
+            Column::Z => "z".to_string(),
+            Column::LookupSorted(i) => format!("s{i}"),
+            Column::LookupAggreg => "a".to_string(),
+            Column::LookupTable => "t".to_string(),
+            Column::LookupKindIndex(i) => format!("k({:?})", i),
+            Column::LookupRuntimeSelector => "rts".to_string(),
+            Column::LookupRuntimeTable => "rt".to_string(),
+            Column::Index(gate) => format!("{:?}", gate),
+            Column::Coefficient(i) => format!("c{i}"),
+        }
+    }
+}
+
+impl std::fmt::Display for Column {
+    /// Renders the column as plain text, with witness columns referred to by their index,
+    /// see [Column::text] to use their names instead.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text(&HashMap::new()))
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 /// A type representing a variable which can appear in a constraint. It specifies a column
 /// and a relative position (Curr or Next)
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
 pub struct Variable {
     /// The column of this variable
     pub col: Column,
@@ -219,9 +372,17 @@ impl Variable {
             Next => format!("\\tilde{{{col}}}"),
         }
     }
+
+    fn text(&self, names: &HashMap<usize, String>) -> String {
+        let col = self.col.text(names);
+        match self.row {
+            Curr => col,
+            Next => format!("{col}'"),
+        }
+    }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 /// An arithmetic expression over
 ///
 /// - the operations *, +, -, ^
@@ -246,6 +407,18 @@ pub enum ConstantExpr<F> {
     Sub(Box<ConstantExpr<F>>, Box<ConstantExpr<F>>),
 }
 
+impl<F> ConstantExpr<F> {
+    /// The largest exponent of the constant expression.
+    fn max_power(&self) -> u64 {
+        use ConstantExpr::*;
+        match self {
+            Alpha | Beta | Gamma | JointCombiner | EndoCoefficient | Mds { .. } | Literal(_) => 0,
+            Pow(x, p) => std::cmp::max(*p, x.max_power()),
+            Add(x, y) | Mul(x, y) | Sub(x, y) => std::cmp::max(x.max_power(), y.max_power()),
+        }
+    }
+}
+
 impl<F: Copy> ConstantExpr<F> {
     fn to_polish_(&self, res: &mut Vec<PolishToken<F>>) {
         match self {
@@ -371,7 +544,7 @@ impl Cache {
 }
 
 /// A binary operation
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Op2 {
     Add,
     Mul,
@@ -395,11 +568,12 @@ impl Op2 {
 /// - `Cell(v)` for `v : Variable`
 /// - VanishesOnLast4Rows
 /// - UnnormalizedLagrangeBasis(i) for `i : i32`
+/// - Parameter(name), a named constant bound with [Expr::bind_parameters]
 ///
 /// This represents a PLONK "custom constraint", which enforces that
 /// the corresponding combination of the polynomials corresponding to
 /// the above variables should vanish on the PLONK domain.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Expr<C> {
     Constant(C),
     Cell(Variable),
@@ -412,6 +586,9 @@ pub enum Expr<C> {
     UnnormalizedLagrangeBasis(i32),
     Pow(Box<Expr<C>>, u64),
     Cache(CacheId, Box<Expr<C>>),
+    /// A named constant, which has to be bound to a value
+    /// (see [Expr::bind_parameters]) before the expression can be evaluated.
+    Parameter(String),
 }
 
 /// For efficiency of evaluation, we compile expressions to
@@ -440,29 +617,36 @@ pub enum PolishToken<F> {
 
 impl Variable {
     fn evaluate<F: Field>(&self, evals: &[ProofEvaluations<F>]) -> Result<F, ExprError> {
-        let evals = &evals[self.row.shift()];
         use Column::*;
-        let l = evals
-            .lookup
-            .as_ref()
-            .ok_or(ExprError::LookupShouldNotBeUsed);
         match self.col {
-            Witness(i) => Ok(evals.w[i]),
-            Z => Ok(evals.z),
-            LookupSorted(i) => l.map(|l| l.sorted[i]),
-            LookupAggreg => l.map(|l| l.aggreg),
-            LookupTable => l.map(|l| l.table),
-            LookupRuntimeTable => l.and_then(|l| l.runtime.ok_or(ExprError::MissingRuntime)),
-            Index(GateType::Poseidon) => Ok(evals.poseidon_selector),
-            Index(GateType::Generic) => Ok(evals.generic_selector),
-            Coefficient(_) | LookupKindIndex(_) | LookupRuntimeSelector | Index(_) => {
-                Err(ExprError::MissingIndexEvaluation(self.col))
+            Coefficient(_) | LookupKindIndex(_) | LookupRuntimeSelector | Index(_)
+                if !matches!(self.col, Index(GateType::Poseidon | GateType::Generic)) =>
+            {
+                return Err(ExprError::MissingIndexEvaluation(self.col))
             }
-        }
+            _ => (),
+        };
+        evals
+            .get(self.row.shift())
+            .and_then(|evals| evals.get(self.col))
+            .copied()
+            .ok_or(ExprError::MissingEvaluation(self.col, self.row))
     }
 }
 
 impl<F: FftField> PolishToken<F> {
+    /// Checks that no [PolishToken::Pow] of `toks` has an exponent larger than `max_power`,
+    /// as [Expr::check_powers] does for expressions.
+    pub fn check_powers(toks: &[PolishToken<F>], max_power: u64) -> Result<(), ExprError> {
+        match toks.iter().find_map(|t| match t {
+            PolishToken::Pow(p) if *p > max_power => Some(*p),
+            _ => None,
+        }) {
+            Some(p) => Err(ExprError::PowerTooLarge(p, max_power)),
+            None => Ok(()),
+        }
+    }
+
     /// Evaluate an RPN expression to a field element.
     pub fn evaluate(
         toks: &[PolishToken<F>],
@@ -533,6 +717,18 @@ impl<C> Expr<C> {
         Expr::Cell(Variable { col, row })
     }
 
+    /// The witness column `I` on the current row.
+    /// Fails to compile if `I` is not a valid witness column.
+    pub fn w_curr<const I: usize>() -> Expr<C> {
+        Expr::Cell(witness_var::<I>(CurrOrNext::Curr))
+    }
+
+    /// The witness column `I` on the next row.
+    /// Fails to compile if `I` is not a valid witness column.
+    pub fn w_next<const I: usize>() -> Expr<C> {
+        Expr::Cell(witness_var::<I>(CurrOrNext::Next))
+    }
+
     pub fn double(self) -> Self {
         Expr::Double(Box::new(self))
     }
@@ -555,12 +751,65 @@ impl<C> Expr<C> {
             UnnormalizedLagrangeBasis(_) => d1_size,
             Cell(_) => d1_size,
             Square(x) => 2 * x.degree(d1_size),
+            // degrees add under multiplication, so a constant factor (degree 0)
+            // leaves the degree of the other factor unchanged
             BinOp(Op2::Mul, x, y) => (*x).degree(d1_size) + (*y).degree(d1_size),
             BinOp(Op2::Add, x, y) | BinOp(Op2::Sub, x, y) => {
                 std::cmp::max((*x).degree(d1_size), (*y).degree(d1_size))
             }
             Pow(e, d) => d * e.degree(d1_size),
             Cache(_, e) => e.degree(d1_size),
+            Parameter(_) => 0,
+        }
+    }
+}
+
+impl<C: Clone + Ord> Expr<C> {
+    /// Returns an equivalent expression in which chains of the commutative operations
+    /// `+` and `*` are flattened and their operands sorted,
+    /// so that expressions that only differ in the order of those operands compare equal.
+    pub fn canonicalize(&self) -> Self {
+        use Expr::*;
+        match self {
+            BinOp(op @ (Op2::Add | Op2::Mul), _, _) => {
+                let mut operands = vec![];
+                self.collect_operands(op, &mut operands);
+                let mut operands: Vec<_> = operands.into_iter().map(Self::canonicalize).collect();
+                operands.sort();
+
+                let mut operands = operands.into_iter();
+                let first = operands
+                    .next()
+                    .expect("a binary operation has two operands");
+                operands.fold(first, |acc, e| {
+                    BinOp(op.clone(), Box::new(acc), Box::new(e))
+                })
+            }
+            BinOp(Op2::Sub, x, y) => BinOp(
+                Op2::Sub,
+                Box::new(x.canonicalize()),
+                Box::new(y.canonicalize()),
+            ),
+            Double(x) => Double(Box::new(x.canonicalize())),
+            Square(x) => Square(Box::new(x.canonicalize())),
+            Pow(x, d) => Pow(Box::new(x.canonicalize()), *d),
+            Cache(id, x) => Cache(*id, Box::new(x.canonicalize())),
+            Constant(_)
+            | Cell(_)
+            | VanishesOnLast4Rows
+            | UnnormalizedLagrangeBasis(_)
+            | Parameter(_) => self.clone(),
+        }
+    }
+
+    /// Collects the operands of a chain of `op` operations.
+    fn collect_operands<'a>(&'a self, op: &Op2, res: &mut Vec<&'a Self>) {
+        match self {
+            Expr::BinOp(o, x, y) if o == op => {
+                x.collect_operands(op, res);
+                y.collect_operands(op, res);
+            }
+            _ => res.push(self),
         }
     }
 }
@@ -573,6 +822,18 @@ enum Domain {
     D8 = 8,
 }
 
+impl Domain {
+    /// The ratio between the size of this domain and the size of d1.
+    fn scale(self) -> usize {
+        self as usize
+    }
+
+    /// All the domains, from the smallest to the largest.
+    fn all() -> [Domain; 4] {
+        [Domain::D1, Domain::D2, Domain::D4, Domain::D8]
+    }
+}
+
 #[derive(Clone)]
 enum EvalResult<'a, F: FftField> {
     Constant(F),
@@ -591,6 +852,30 @@ enum EvalResult<'a, F: FftField> {
     },
 }
 
+/// Reports the domains and the number of evaluations, but not the evaluations themselves.
+impl<'a, F: FftField> std::fmt::Debug for EvalResult<'a, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvalResult::Constant(x) => f.debug_tuple("Constant").field(x).finish(),
+            EvalResult::Evals { domain, evals } => f
+                .debug_struct("Evals")
+                .field("domain", domain)
+                .field("len", &evals.evals.len())
+                .finish(),
+            EvalResult::SubEvals {
+                domain,
+                shift,
+                evals,
+            } => f
+                .debug_struct("SubEvals")
+                .field("domain", domain)
+                .field("shift", shift)
+                .field("len", &evals.evals.len())
+                .finish(),
+        }
+    }
+}
+
 /// Compute the powers of `x`, `x^0, ..., x^{n - 1}`
 pub fn pows<F: Field>(x: F, n: usize) -> Vec<F> {
     if n == 0 {
@@ -605,6 +890,31 @@ pub fn pows<F: Field>(x: F, n: usize) -> Vec<F> {
     v
 }
 
+/// Subtracts `other` from `evals`, the evaluations of two polynomials over possibly different domains,
+/// and returns the evaluations of the difference over `target`.
+/// Evaluations over a domain smaller than `target` are interpolated and evaluated over `target`,
+/// and evaluations over a larger domain are subsampled, so `target` must be a subgroup of it.
+/// This lets constraint authors combine contributions of different natural degrees,
+/// for example evaluated over `d4` and `d8`, where subtracting the [Evaluations] directly panics.
+pub fn sub_upsampled<F: FftField>(
+    evals: Evaluations<F, D<F>>,
+    other: Evaluations<F, D<F>>,
+    target: D<F>,
+) -> Evaluations<F, D<F>> {
+    let into_target = |e: Evaluations<F, D<F>>| {
+        let size = e.domain().size();
+        if size == target.size() {
+            e
+        } else if size < target.size() {
+            e.interpolate().evaluate_over_domain(target)
+        } else {
+            let scale = size / target.size();
+            Evaluations::from_vec_and_domain(e.evals.into_iter().step_by(scale).collect(), target)
+        }
+    };
+    &into_target(evals) - &into_target(other)
+}
+
 /// Compute the evaluations of the unnormalized lagrange polynomial on
 /// H_8 or H_4. Taking H_8 as an example, we show how to compute this
 /// polynomial on the expanded domain.
@@ -645,12 +955,7 @@ fn unnormalized_lagrange_evals<F: FftField>(
     res_domain: Domain,
     env: &Environment<F>,
 ) -> Evaluations<F, D<F>> {
-    let k = match res_domain {
-        Domain::D1 => 1,
-        Domain::D2 => 2,
-        Domain::D4 => 4,
-        Domain::D8 => 8,
-    };
+    let k = res_domain.scale();
     let res_domain = get_domain(res_domain, env);
 
     let d1 = env.domain.d1;
@@ -756,12 +1061,12 @@ impl<'a, F: FftField> EvalResult<'a, F> {
                 },
             ) => {
                 let n = res_domain.1.size();
-                let scale = (domain as usize) / (res_domain.0 as usize);
+                let scale = domain.scale() / res_domain.0.scale();
                 assert!(scale != 0);
                 let v: Vec<_> = (0..n)
                     .into_par_iter()
                     .map(|i| {
-                        x + evals.evals[(scale * i + (domain as usize) * shift) % evals.evals.len()]
+                        x + evals.evals[(scale * i + domain.scale() * shift) % evals.evals.len()]
                     })
                     .collect();
                 Evals {
@@ -808,10 +1113,10 @@ impl<'a, F: FftField> EvalResult<'a, F> {
                     evals: es_sub,
                 },
             ) => {
-                let scale = (d_sub as usize) / (d as usize);
+                let scale = d_sub.scale() / d.scale();
                 assert!(scale != 0);
                 evals.evals.par_iter_mut().enumerate().for_each(|(i, e)| {
-                    *e += es_sub.evals[(scale * i + (d_sub as usize) * s) % es_sub.evals.len()];
+                    *e += es_sub.evals[(scale * i + d_sub.scale() * s) % es_sub.evals.len()];
                 });
                 Evals { evals, domain: d }
             }
@@ -827,17 +1132,17 @@ impl<'a, F: FftField> EvalResult<'a, F> {
                     evals: es2,
                 },
             ) => {
-                let scale1 = (d1 as usize) / (res_domain.0 as usize);
+                let scale1 = d1.scale() / res_domain.0.scale();
                 assert!(scale1 != 0);
-                let scale2 = (d2 as usize) / (res_domain.0 as usize);
+                let scale2 = d2.scale() / res_domain.0.scale();
                 assert!(scale2 != 0);
 
                 let n = res_domain.1.size();
                 let v: Vec<_> = (0..n)
                     .into_par_iter()
                     .map(|i| {
-                        es1.evals[(scale1 * i + (d1 as usize) * s1) % es1.evals.len()]
-                            + es2.evals[(scale2 * i + (d2 as usize) * s2) % es2.evals.len()]
+                        es1.evals[(scale1 * i + d1.scale() * s1) % es1.evals.len()]
+                            + es2.evals[(scale2 * i + d2.scale() * s2) % es2.evals.len()]
                     })
                     .collect();
 
@@ -873,10 +1178,10 @@ impl<'a, F: FftField> EvalResult<'a, F> {
                 },
                 Constant(x),
             ) => {
-                let scale = (d as usize) / (res_domain.0 as usize);
+                let scale = d.scale() / res_domain.0.scale();
                 assert!(scale != 0);
                 EvalResult::init(res_domain, |i| {
-                    evals.evals[(scale * i + (d as usize) * s) % evals.evals.len()] - x
+                    evals.evals[(scale * i + d.scale() * s) % evals.evals.len()] - x
                 })
             }
             (
@@ -887,10 +1192,10 @@ impl<'a, F: FftField> EvalResult<'a, F> {
                     shift: s,
                 },
             ) => {
-                let scale = (d as usize) / (res_domain.0 as usize);
+                let scale = d.scale() / res_domain.0.scale();
                 assert!(scale != 0);
                 EvalResult::init(res_domain, |i| {
-                    x - evals.evals[(scale * i + (d as usize) * s) % evals.evals.len()]
+                    x - evals.evals[(scale * i + d.scale() * s) % evals.evals.len()]
                 })
             }
             (
@@ -921,10 +1226,10 @@ impl<'a, F: FftField> EvalResult<'a, F> {
                     mut evals,
                 },
             ) => {
-                let scale = (d_sub as usize) / (d as usize);
+                let scale = d_sub.scale() / d.scale();
                 assert!(scale != 0);
                 evals.evals.par_iter_mut().enumerate().for_each(|(i, e)| {
-                    *e = es_sub.evals[(scale * i + (d_sub as usize) * s) % es_sub.evals.len()] - *e;
+                    *e = es_sub.evals[(scale * i + d_sub.scale() * s) % es_sub.evals.len()] - *e;
                 });
                 Evals { evals, domain: d }
             }
@@ -939,10 +1244,10 @@ impl<'a, F: FftField> EvalResult<'a, F> {
                     evals: es_sub,
                 },
             ) => {
-                let scale = (d_sub as usize) / (d as usize);
+                let scale = d_sub.scale() / d.scale();
                 assert!(scale != 0);
                 evals.evals.par_iter_mut().enumerate().for_each(|(i, e)| {
-                    *e -= es_sub.evals[(scale * i + (d_sub as usize) * s) % es_sub.evals.len()];
+                    *e -= es_sub.evals[(scale * i + d_sub.scale() * s) % es_sub.evals.len()];
                 });
                 Evals { evals, domain: d }
             }
@@ -958,14 +1263,14 @@ impl<'a, F: FftField> EvalResult<'a, F> {
                     evals: es2,
                 },
             ) => {
-                let scale1 = (d1 as usize) / (res_domain.0 as usize);
+                let scale1 = d1.scale() / res_domain.0.scale();
                 assert!(scale1 != 0);
-                let scale2 = (d2 as usize) / (res_domain.0 as usize);
+                let scale2 = d2.scale() / res_domain.0.scale();
                 assert!(scale2 != 0);
 
                 EvalResult::init(res_domain, |i| {
-                    es1.evals[(scale1 * i + (d1 as usize) * s1) % es1.evals.len()]
-                        - es2.evals[(scale2 * i + (d2 as usize) * s2) % es2.evals.len()]
+                    es1.evals[(scale1 * i + d1.scale() * s1) % es1.evals.len()]
+                        - es2.evals[(scale2 * i + d2.scale() * s2) % es2.evals.len()]
                 })
             }
         }
@@ -999,10 +1304,10 @@ impl<'a, F: FftField> EvalResult<'a, F> {
                 domain: d,
                 shift: s,
             } => {
-                let scale = (d as usize) / (res_domain.0 as usize);
+                let scale = d.scale() / res_domain.0.scale();
                 assert!(scale != 0);
                 EvalResult::init(res_domain, |i| {
-                    evals.evals[(scale * i + (d as usize) * s) % evals.evals.len()].square()
+                    evals.evals[(scale * i + d.scale() * s) % evals.evals.len()].square()
                 })
             }
         }
@@ -1037,10 +1342,10 @@ impl<'a, F: FftField> EvalResult<'a, F> {
                     shift: s,
                 },
             ) => {
-                let scale = (d as usize) / (res_domain.0 as usize);
+                let scale = d.scale() / res_domain.0.scale();
                 assert!(scale != 0);
                 EvalResult::init(res_domain, |i| {
-                    x * evals.evals[(scale * i + (d as usize) * s) % evals.evals.len()]
+                    x * evals.evals[(scale * i + d.scale() * s) % evals.evals.len()]
                 })
             }
             (
@@ -1082,10 +1387,10 @@ impl<'a, F: FftField> EvalResult<'a, F> {
                     evals: es_sub,
                 },
             ) => {
-                let scale = (d_sub as usize) / (d as usize);
+                let scale = d_sub.scale() / d.scale();
                 assert!(scale != 0);
                 evals.evals.par_iter_mut().enumerate().for_each(|(i, e)| {
-                    *e *= es_sub.evals[(scale * i + (d_sub as usize) * s) % es_sub.evals.len()];
+                    *e *= es_sub.evals[(scale * i + d_sub.scale() * s) % es_sub.evals.len()];
                 });
                 Evals { evals, domain: d }
             }
@@ -1101,14 +1406,55 @@ impl<'a, F: FftField> EvalResult<'a, F> {
                     evals: es2,
                 },
             ) => {
-                let scale1 = (d1 as usize) / (res_domain.0 as usize);
+                let scale1 = d1.scale() / res_domain.0.scale();
                 assert!(scale1 != 0);
-                let scale2 = (d2 as usize) / (res_domain.0 as usize);
+                let scale2 = d2.scale() / res_domain.0.scale();
                 assert!(scale2 != 0);
 
                 EvalResult::init(res_domain, |i| {
-                    es1.evals[(scale1 * i + (d1 as usize) * s1) % es1.evals.len()]
-                        * es2.evals[(scale2 * i + (d2 as usize) * s2) % es2.evals.len()]
+                    es1.evals[(scale1 * i + d1.scale() * s1) % es1.evals.len()]
+                        * es2.evals[(scale2 * i + d2.scale() * s2) % es2.evals.len()]
+                })
+            }
+        }
+    }
+}
+
+/// The smallest of the domains (but d2) over which a polynomial of degree `deg` can be evaluated.
+fn evaluations_domain(deg: u64, d1_size: u64) -> Domain {
+    Domain::all()
+        .into_iter()
+        .filter(|d| *d != Domain::D2)
+        .find(|d| deg <= d.scale() as u64 * d1_size)
+        .unwrap_or_else(|| {
+            let max = *Domain::all().last().unwrap();
+            panic!(
+                "constraint had degree {deg} > {:?} ({})",
+                max,
+                max.scale() as u64 * d1_size
+            )
+        })
+}
+
+impl<'a, F: FftField> EvalResult<'a, F> {
+    /// The evaluations of this result over the domain `d`.
+    fn into_evaluations(self, d: Domain, env: &Environment<F>) -> Evaluations<F, D<F>> {
+        match self {
+            EvalResult::Evals { evals, domain } => {
+                assert_eq!(domain, d);
+                evals
+            }
+            EvalResult::Constant(x) => EvalResult::init_((d, get_domain(d, env)), |_| x),
+            EvalResult::SubEvals {
+                evals,
+                domain: d_sub,
+                shift: s,
+            } => {
+                let res_domain = get_domain(d, env);
+                let scale = d_sub.scale() / d.scale();
+                assert!(scale != 0);
+                EvalResult::init_((d, res_domain), |i| {
+                    evals.evals[(scale * i + d_sub.scale() * s) % evals.evals.len()]
                 })
             }
         }
@@ -1131,6 +1477,33 @@ impl<F: Field> Expr<ConstantExpr<F>> {
         Expr::Constant(ConstantExpr::Literal(x))
     }
 
+    /// Checks that no exponent of the expression, constants included, exceeds `max_power`.
+    /// The degree of the expression grows with its exponents, so this should be checked
+    /// before evaluating an expression that was not built by the circuit itself.
+    pub fn check_powers(&self, max_power: u64) -> Result<(), ExprError> {
+        use Expr::*;
+        let check = |p: u64| {
+            if p > max_power {
+                Err(ExprError::PowerTooLarge(p, max_power))
+            } else {
+                Ok(())
+            }
+        };
+        match self {
+            Constant(c) => check(c.max_power()),
+            Pow(x, p) => {
+                check(*p)?;
+                x.check_powers(max_power)
+            }
+            Double(x) | Square(x) | Cache(_, x) => x.check_powers(max_power),
+            BinOp(_, x, y) => {
+                x.check_powers(max_power)?;
+                y.check_powers(max_power)
+            }
+            Cell(_) | VanishesOnLast4Rows | UnnormalizedLagrangeBasis(_) | Parameter(_) => Ok(()),
+        }
+    }
+
     /// Combines multiple constraints `[c0, ..., cn]` into a single constraint
     /// `alpha^alpha0 * c0 + alpha^{alpha0 + 1} * c1 + ... + alpha^{alpha0 + n} * cn`.
     pub fn combine_constraints(alphas: impl Iterator<Item = u32>, cs: Vec<Self>) -> Self {
@@ -1140,10 +1513,57 @@ impl<F: Field> Expr<ConstantExpr<F>> {
             .map(|(c, i)| Expr::Constant(ConstantExpr::Alpha.pow(i as u64)) * c)
             .fold(zero, |acc, x| acc + x)
     }
+
+    /// Combines multiple constraints `[c0, ..., cn]` into the same constraint as
+    /// [Expr::combine_constraints], using Horner's scheme:
+    /// `alpha^alpha0 * (c0 + alpha * (c1 + ... + alpha * cn))`.
+    /// This only refers to `alpha` itself, so evaluating the result takes
+    /// `n` multiplications instead of `n` exponentiations.
+    pub fn combine_constraints_horner(alpha0: u32, cs: Vec<Self>) -> Self {
+        let alpha = || Expr::Constant(ConstantExpr::Alpha);
+        let combined = cs
+            .into_iter()
+            .rev()
+            .reduce(|acc, c| c + alpha() * acc)
+            .unwrap_or_else(Expr::zero);
+        if alpha0 == 0 {
+            combined
+        } else {
+            Expr::Constant(ConstantExpr::Alpha.pow(alpha0 as u64)) * combined
+        }
+    }
+
+    /// Replaces every [Expr::Parameter] in the expression by its value in `params`.
+    /// This allows building a gate once and instantiating it with different constants.
+    pub fn bind_parameters(&self, params: &HashMap<String, F>) -> Result<Self, ExprError> {
+        use Expr::*;
+        let res = match self {
+            Parameter(name) => match params.get(name) {
+                Some(x) => Self::literal(*x),
+                None => return Err(ExprError::UnboundParameter(name.clone())),
+            },
+            Constant(c) => Constant(c.clone()),
+            Cell(v) => Cell(*v),
+            Double(x) => Double(Box::new(x.bind_parameters(params)?)),
+            Square(x) => Square(Box::new(x.bind_parameters(params)?)),
+            BinOp(op, x, y) => BinOp(
+                op.clone(),
+                Box::new(x.bind_parameters(params)?),
+                Box::new(y.bind_parameters(params)?),
+            ),
+            VanishesOnLast4Rows => VanishesOnLast4Rows,
+            UnnormalizedLagrangeBasis(i) => UnnormalizedLagrangeBasis(*i),
+            Pow(x, d) => Pow(Box::new(x.bind_parameters(params)?), *d),
+            Cache(id, x) => Cache(*id, Box::new(x.bind_parameters(params)?)),
+        };
+        Ok(res)
+    }
 }
 
 impl<F: FftField> Expr<ConstantExpr<F>> {
     /// Compile an expression to an RPN expression.
+    /// It panics if the expression has a [Parameter](Expr::Parameter) that was not bound
+    /// with [Expr::bind_parameters].
     pub fn to_polish(&self) -> Vec<PolishToken<F>> {
         let mut res = vec![];
         let mut cache = HashMap::new();
@@ -1197,6 +1617,7 @@ impl<F: FftField> Expr<ConstantExpr<F>> {
                     }
                 }
             }
+            Expr::Parameter(name) => panic!("parameter {name} is not bound"),
         }
     }
 
@@ -1220,6 +1641,7 @@ impl<F: FftField> Expr<ConstantExpr<F>> {
             BinOp(Op2::Mul, x, y) => x.evaluate_constants_(c) * y.evaluate_constants_(c),
             BinOp(Op2::Sub, x, y) => x.evaluate_constants_(c) - y.evaluate_constants_(c),
             Cache(id, e) => Cache(*id, Box::new(e.evaluate_constants_(c))),
+            Parameter(name) => Parameter(name.clone()),
         }
     }
 
@@ -1267,18 +1689,105 @@ impl<F: FftField> Expr<ConstantExpr<F>> {
             UnnormalizedLagrangeBasis(i) => Ok(unnormalized_lagrange_basis(&d, *i, &pt)),
             Cell(v) => v.evaluate(evals),
             Cache(_, e) => e.evaluate_(d, pt, evals, c),
+            Parameter(name) => Err(ExprError::UnboundParameter(name.clone())),
         }
     }
 
+    /// Same as [Expr::univariate_in] for an expression with constants to evaluate against `c`.
+    pub fn univariate_in(
+        &self,
+        var: Variable,
+        d: D<F>,
+        pt: F,
+        evals: &[ProofEvaluations<F>],
+        c: &Constants<F>,
+    ) -> Result<DensePolynomial<F>, ExprError> {
+        self.evaluate_constants_(c).univariate_in(var, d, pt, evals)
+    }
+
     /// Evaluate the constant expressions in this expression down into field elements.
     pub fn evaluate_constants(&self, env: &Environment<F>) -> Expr<F> {
         self.evaluate_constants_(&env.constants)
     }
 
     /// Compute the polynomial corresponding to this expression, in evaluation form.
+    /// It panics if the expression has a [Parameter](Expr::Parameter) that was not bound
+    /// with [Expr::bind_parameters].
     pub fn evaluations<'a>(&self, env: &Environment<'a, F>) -> Evaluations<F, D<F>> {
         self.evaluate_constants(env).evaluations(env)
     }
+
+    /// Compute the polynomial corresponding to this expression, in coefficient form.
+    /// See [Expr::to_polynomial] on evaluated expressions.
+    pub fn to_polynomial<'a>(&self, env: &Environment<'a, F>) -> DensePolynomial<F> {
+        self.evaluate_constants(env).to_polynomial(env)
+    }
+
+    /// Asserts that the linearization of this expression with respect to the `evaluated` columns
+    /// is the expression itself, by comparing the evaluations of both over the domain d8.
+    #[cfg(test)]
+    pub fn assert_linearization_correct(&self, evaluated: HashSet<Column>, env: &Environment<F>) {
+        let reconstructed = self.linearize(evaluated).unwrap().to_expr();
+
+        let d8 = env.domain.d8;
+        let expected = self.evaluations_on_subdomain(env, d8);
+        let got = reconstructed.evaluations_on_subdomain(env, d8);
+        for (i, (x, y)) in expected.evals.iter().zip(&got.evals).enumerate() {
+            assert_eq!(
+                x, y,
+                "the linearization differs at the {i}-th element of d8"
+            );
+        }
+    }
+
+    /// Compute the evaluations of the polynomial corresponding to this expression
+    /// on the subdomain `sub` (for example `d1`) only.
+    /// If `sub` is a subgroup of the domain the expression is naturally evaluated on,
+    /// the evaluations are sampled from it, otherwise the polynomial is interpolated
+    /// and evaluated over `sub`.
+    pub fn evaluations_on_subdomain<'a>(
+        &self,
+        env: &Environment<'a, F>,
+        sub: D<F>,
+    ) -> Evaluations<F, D<F>> {
+        let evals = self.evaluations(env);
+        let n = evals.domain().size();
+        let m = sub.size();
+        if m <= n && n % m == 0 {
+            // the i-th element of `sub` is the (i * n / m)-th element of the larger domain
+            let sampled = evals.evals.iter().step_by(n / m).copied().collect();
+            Evaluations::from_vec_and_domain(sampled, sub)
+        } else {
+            evals.interpolate().evaluate_over_domain(sub)
+        }
+    }
+
+    /// Evaluates each monomial of the expression on the given row of the domain `d1`.
+    /// See [Expr::evaluate_monomials] on evaluated expressions.
+    pub fn evaluate_monomials(
+        &self,
+        env: &Environment<F>,
+        row: usize,
+    ) -> Result<Vec<(Vec<Variable>, F)>, ExprError> {
+        self.evaluate_constants(env).evaluate_monomials(env, row)
+    }
+
+    /// Checks that every constraint in `cs` vanishes on every row of the domain `d1`,
+    /// without combining them with powers of alpha.
+    /// On failure, returns the index of the first violated constraint
+    /// and the row at which it does not vanish.
+    pub fn all_zero(cs: &[Self], env: &Environment<F>) -> Result<(), (usize, usize)> {
+        let n = env.domain.d1.size();
+        for (i, c) in cs.iter().enumerate() {
+            let evals = c.evaluations(env);
+            // the evaluations may be over a larger domain, only look at the rows of d1
+            let scale = evals.evals.len() / n;
+            if let Some(row) = (0..n).find(|row| !evals.evals[scale * row].is_zero()) {
+                return Err((i, row));
+            }
+        }
+        Ok(())
+    }
 }
 
 enum Either<A, B> {
@@ -1314,22 +1823,63 @@ impl<F: FftField> Expr<F> {
             UnnormalizedLagrangeBasis(i) => Ok(unnormalized_lagrange_basis(&d, *i, &pt)),
             Cell(v) => v.evaluate(evals),
             Cache(_, e) => e.evaluate(d, pt, evals),
+            Parameter(name) => Err(ExprError::UnboundParameter(name.clone())),
+        }
+    }
+
+    /// Specializes the expression into a univariate polynomial in the variable `var`,
+    /// by substituting the evaluations `evals` (at `pt`) for all the other cells.
+    /// The roots of the result are the values of `var` that satisfy the constraint.
+    pub fn univariate_in(
+        &self,
+        var: Variable,
+        d: D<F>,
+        pt: F,
+        evals: &[ProofEvaluations<F>],
+    ) -> Result<DensePolynomial<F>, ExprError> {
+        use Expr::*;
+        let constant = |x: F| DensePolynomial::from_coefficients_vec(vec![x]);
+        match self {
+            Constant(x) => Ok(constant(*x)),
+            Pow(x, p) => {
+                let x = x.univariate_in(var, d, pt, evals)?;
+                Ok((0..*p).fold(constant(F::one()), |acc, _| &acc * &x))
+            }
+            Double(x) => {
+                let x = x.univariate_in(var, d, pt, evals)?;
+                Ok(&x + &x)
+            }
+            Square(x) => {
+                let x = x.univariate_in(var, d, pt, evals)?;
+                Ok(&x * &x)
+            }
+            BinOp(op, x, y) => {
+                let x = x.univariate_in(var, d, pt, evals)?;
+                let y = y.univariate_in(var, d, pt, evals)?;
+                Ok(match op {
+                    Op2::Mul => &x * &y,
+                    Op2::Add => &x + &y,
+                    Op2::Sub => &x - &y,
+                })
+            }
+            VanishesOnLast4Rows => Ok(constant(eval_vanishes_on_last_4_rows(d, pt))),
+            UnnormalizedLagrangeBasis(i) => Ok(constant(unnormalized_lagrange_basis(&d, *i, &pt))),
+            Cell(v) if *v == var => Ok(DensePolynomial::from_coefficients_vec(vec![
+                F::zero(),
+                F::one(),
+            ])),
+            Cell(v) => v.evaluate(evals).map(constant),
+            Cache(_, e) => e.univariate_in(var, d, pt, evals),
+            Parameter(name) => Err(ExprError::UnboundParameter(name.clone())),
         }
     }
 
     /// Compute the polynomial corresponding to this expression, in evaluation form.
+    /// It panics if the expression has a [Parameter](Expr::Parameter) that was not bound
+    /// with [Expr::bind_parameters].
     pub fn evaluations<'a>(&self, env: &Environment<'a, F>) -> Evaluations<F, D<F>> {
         let d1_size = env.domain.d1.size;
-        let deg = self.degree(d1_size);
-        let d = if deg <= d1_size {
-            Domain::D1
-        } else if deg <= 4 * d1_size {
-            Domain::D4
-        } else if deg <= 8 * d1_size {
-            Domain::D8
-        } else {
-            panic!("constraint had degree {deg} > d8 ({})", 8 * d1_size);
-        };
+        let d = evaluations_domain(self.degree(d1_size), d1_size);
 
         let mut cache = HashMap::new();
 
@@ -1338,25 +1888,63 @@ impl<F: FftField> Expr<F> {
             Either::Right(id) => cache.get(&id).unwrap().clone(),
         };
 
-        match evals {
-            EvalResult::Evals { evals, domain } => {
-                assert_eq!(domain, d);
-                evals
-            }
-            EvalResult::Constant(x) => EvalResult::init_((d, get_domain(d, env)), |_| x),
-            EvalResult::SubEvals {
-                evals,
-                domain: d_sub,
-                shift: s,
-            } => {
-                let res_domain = get_domain(d, env);
-                let scale = (d_sub as usize) / (d as usize);
-                assert!(scale != 0);
-                EvalResult::init_((d, res_domain), |i| {
-                    evals.evals[(scale * i + (d_sub as usize) * s) % evals.evals.len()]
-                })
-            }
-        }
+        evals.into_evaluations(d, env)
+    }
+
+    /// Compute the polynomial corresponding to this expression, in coefficient form,
+    /// by interpolating its [evaluations](Expr::evaluations)
+    /// over the domain chosen from the degree of the expression.
+    /// In debug builds, this checks that the polynomial is not of larger degree than predicted.
+    pub fn to_polynomial<'a>(&self, env: &Environment<'a, F>) -> DensePolynomial<F> {
+        let poly = self.evaluations(env).interpolate();
+        debug_assert!(
+            poly.degree() as u64 <= self.degree(env.domain.d1.size),
+            "the polynomial has degree {}, more than the predicted {}",
+            poly.degree(),
+            self.degree(env.domain.d1.size)
+        );
+        poly
+    }
+
+    /// Decomposes the expression in monomials, and evaluates each of them
+    /// (coefficient included) on the given row of the domain `d1`.
+    /// This is useful to find out which term of a constraint does not vanish.
+    /// The monomials are returned sorted, and their contributions sum up to the expression.
+    /// Index columns absent from `env` are zero, as in [Expr::evaluations],
+    /// but any other column absent from `env` is reported as [ExprError::MissingColumn].
+    pub fn evaluate_monomials(
+        &self,
+        env: &Environment<F>,
+        row: usize,
+    ) -> Result<Vec<(Vec<Variable>, F)>, ExprError> {
+        let n = env.domain.d1.size();
+        // the evaluations can be over a larger domain than d1, in which case row i is at index scale * i
+        let at_row = |evals: &Evaluations<F, D<F>>, row: usize| {
+            let scale = evals.evals.len() / n;
+            evals.evals[(scale * row) % evals.evals.len()]
+        };
+
+        let value = |v: &Variable| match env.column(&v.col) {
+            Some(evals) => Ok(at_row(evals, row + v.row.shift())),
+            None => match v.col {
+                Column::Index(_) => Ok(F::zero()),
+                col => Err(ExprError::MissingColumn(col, env.column_name(&col))),
+            },
+        };
+
+        let mut res = self
+            .monomials(&HashSet::new())
+            .into_iter()
+            .map(|(vars, coeff)| {
+                let mut acc = at_row(&coeff.evaluations(env), row);
+                for v in &vars {
+                    acc *= value(v)?;
+                }
+                Ok((vars, acc))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        res.sort_by(|(x, _), (y, _)| x.cmp(y));
+        Ok(res)
     }
 
     fn evaluations_helper<'a, 'b>(
@@ -1442,13 +2030,14 @@ impl<F: FftField> Expr<F> {
                 evals: env.vanishes_on_last_4_rows,
             },
             Expr::Constant(x) => EvalResult::Constant(*x),
+            Expr::Parameter(name) => panic!("parameter {name} is not bound"),
             Expr::UnnormalizedLagrangeBasis(i) => EvalResult::Evals {
                 domain: d,
                 evals: unnormalized_lagrange_evals(env.l0_1, *i, d, env),
             },
             Expr::Cell(Variable { col, row }) => {
                 let evals: &'a Evaluations<F, D<F>> = {
-                    match env.get_column(col) {
+                    match env.column(col) {
                         None => return Either::Left(EvalResult::Constant(F::zero())),
                         Some(e) => e,
                     }
@@ -1483,22 +2072,392 @@ impl<F: FftField> Expr<F> {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-/// A "linearization", which is linear combination with `E` coefficients of
-/// columns.
-pub struct Linearization<E> {
-    pub constant_term: E,
-    pub index_terms: Vec<(Column, E)>,
+/// A node of a [FlatExpr]. The operands of a node are the indices of earlier nodes.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FlatNode<C> {
+    Constant(C),
+    Cell(Variable),
+    Double(usize),
+    Square(usize),
+    BinOp(Op2, usize, usize),
+    VanishesOnLast4Rows,
+    UnnormalizedLagrangeBasis(i32),
+    Pow(usize, u64),
+    Parameter(String),
 }
 
-impl<E: Default> Default for Linearization<E> {
-    fn default() -> Self {
-        Linearization {
-            constant_term: E::default(),
-            index_terms: vec![],
+/// An [Expr] stored as an array of nodes, in which the operands of a node come before it
+/// and the root of the expression is the last node.
+/// Equal sub-expressions are stored only once, which makes this representation more compact
+/// than the tree of boxed nodes, and avoids evaluating them several times.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlatExpr<C> {
+    pub nodes: Vec<FlatNode<C>>,
+}
+
+/// Size metrics of an [Expr], see [Expr::stats].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExprStats {
+    /// The number of nodes of the expression, counting the sub-expression
+    /// of a cached node only once
+    pub node_count: usize,
+    /// The number of nodes on the longest path from the root to a leaf
+    pub depth: usize,
+    /// The degree of the expression as a polynomial in its cells
+    pub max_degree: u64,
+    /// The number of distinct columns the expression refers to
+    pub distinct_columns: usize,
+}
+
+impl<C> Expr<C> {
+    /// Checks that every column the expression refers to can be found in `env`,
+    /// and returns all the columns that cannot.
+    /// Witness and coefficient columns must be in range, and lookup columns
+    /// are only available when `env` has a lookup environment.
+    /// Index columns absent from `env.index` evaluate to zero, which is
+    /// only reported as an error if `strict` is set.
+    pub fn validate_against<F: FftField>(
+        &self,
+        env: &Environment<F>,
+        strict: bool,
+    ) -> Result<(), Vec<ExprError>> {
+        let mut missing = vec![];
+        self.validate_against_(env, strict, &mut missing);
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(missing
+                .into_iter()
+                .map(|col| ExprError::MissingColumn(col, env.column_name(&col)))
+                .collect())
         }
     }
-}
+
+    fn validate_against_<F: FftField>(
+        &self,
+        env: &Environment<F>,
+        strict: bool,
+        missing: &mut Vec<Column>,
+    ) {
+        use Expr::*;
+        match self {
+            Constant(_) | VanishesOnLast4Rows | UnnormalizedLagrangeBasis(_) | Parameter(_) => (),
+            Cell(v) => {
+                let found = match v.col {
+                    Column::Witness(i) | Column::Coefficient(i) => i < COLUMNS,
+                    Column::LookupSorted(i) => {
+                        env.lookup.as_ref().map_or(false, |l| i < l.sorted.len())
+                    }
+                    Column::Index(_) if !strict => true,
+                    col => env.column(&col).is_some(),
+                };
+                if !found && !missing.contains(&v.col) {
+                    missing.push(v.col);
+                }
+            }
+            Double(x) | Square(x) | Pow(x, _) | Cache(_, x) => {
+                x.validate_against_(env, strict, missing)
+            }
+            BinOp(_, x, y) => {
+                x.validate_against_(env, strict, missing);
+                y.validate_against_(env, strict, missing);
+            }
+        }
+    }
+
+    /// Walks the expression once and returns its size metrics,
+    /// which help understanding why evaluating it is expensive.
+    pub fn stats(&self) -> ExprStats {
+        let mut node_count = 0;
+        let mut columns = HashSet::new();
+        let (depth, max_degree) = self.stats_(&mut node_count, &mut columns, &mut HashMap::new());
+        ExprStats {
+            node_count,
+            depth,
+            max_degree,
+            distinct_columns: columns.len(),
+        }
+    }
+
+    /// Returns the depth and the degree of the expression.
+    fn stats_(
+        &self,
+        node_count: &mut usize,
+        columns: &mut HashSet<Column>,
+        cached: &mut HashMap<CacheId, (usize, u64)>,
+    ) -> (usize, u64) {
+        use Expr::*;
+        *node_count += 1;
+        let (depth, degree) = match self {
+            Constant(_) | VanishesOnLast4Rows | UnnormalizedLagrangeBasis(_) | Parameter(_) => {
+                (0, 0)
+            }
+            Cell(v) => {
+                columns.insert(v.col);
+                (0, 1)
+            }
+            Double(x) => x.stats_(node_count, columns, cached),
+            Square(x) => {
+                let (depth, degree) = x.stats_(node_count, columns, cached);
+                (depth, 2 * degree)
+            }
+            Pow(x, p) => {
+                let (depth, degree) = x.stats_(node_count, columns, cached);
+                (depth, p * degree)
+            }
+            BinOp(op, x, y) => {
+                let (x_depth, x_degree) = x.stats_(node_count, columns, cached);
+                let (y_depth, y_degree) = y.stats_(node_count, columns, cached);
+                let degree = match op {
+                    Op2::Mul => x_degree + y_degree,
+                    Op2::Add | Op2::Sub => std::cmp::max(x_degree, y_degree),
+                };
+                (std::cmp::max(x_depth, y_depth), degree)
+            }
+            Cache(id, x) => match cached.get(id) {
+                Some(stats) => *stats,
+                None => {
+                    let stats = x.stats_(node_count, columns, cached);
+                    cached.insert(*id, stats);
+                    stats
+                }
+            },
+        };
+        (depth + 1, degree)
+    }
+}
+
+impl<C: Clone + Eq + std::hash::Hash> Expr<C> {
+    /// Stores the expression as a [FlatExpr], sharing its common sub-expressions.
+    pub fn flatten(&self) -> FlatExpr<C> {
+        let mut nodes = vec![];
+        self.flatten_(&mut nodes, &mut HashMap::new(), &mut HashMap::new());
+        FlatExpr { nodes }
+    }
+
+    fn flatten_(
+        &self,
+        nodes: &mut Vec<FlatNode<C>>,
+        indices: &mut HashMap<FlatNode<C>, usize>,
+        cached: &mut HashMap<CacheId, usize>,
+    ) -> usize {
+        use Expr::*;
+        let node = match self {
+            Constant(x) => FlatNode::Constant(x.clone()),
+            Cell(v) => FlatNode::Cell(*v),
+            Double(x) => FlatNode::Double(x.flatten_(nodes, indices, cached)),
+            Square(x) => FlatNode::Square(x.flatten_(nodes, indices, cached)),
+            BinOp(op, x, y) => {
+                let x = x.flatten_(nodes, indices, cached);
+                let y = y.flatten_(nodes, indices, cached);
+                FlatNode::BinOp(op.clone(), x, y)
+            }
+            VanishesOnLast4Rows => FlatNode::VanishesOnLast4Rows,
+            UnnormalizedLagrangeBasis(i) => FlatNode::UnnormalizedLagrangeBasis(*i),
+            Pow(x, p) => FlatNode::Pow(x.flatten_(nodes, indices, cached), *p),
+            Cache(id, e) => {
+                if let Some(i) = cached.get(id) {
+                    return *i;
+                }
+                let i = e.flatten_(nodes, indices, cached);
+                cached.insert(*id, i);
+                return i;
+            }
+            Parameter(name) => FlatNode::Parameter(name.clone()),
+        };
+        match indices.get(&node) {
+            Some(i) => *i,
+            None => {
+                nodes.push(node.clone());
+                indices.insert(node, nodes.len() - 1);
+                nodes.len() - 1
+            }
+        }
+    }
+}
+
+impl<C> FlatExpr<C> {
+    fn degree(&self, d1_size: u64) -> u64 {
+        let mut degrees: Vec<u64> = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            let deg = match node {
+                FlatNode::Constant(_) | FlatNode::Parameter(_) => 0,
+                FlatNode::VanishesOnLast4Rows => 4,
+                FlatNode::UnnormalizedLagrangeBasis(_) | FlatNode::Cell(_) => d1_size,
+                FlatNode::Double(x) => degrees[*x],
+                FlatNode::Square(x) => 2 * degrees[*x],
+                FlatNode::BinOp(Op2::Mul, x, y) => degrees[*x] + degrees[*y],
+                FlatNode::BinOp(Op2::Add, x, y) | FlatNode::BinOp(Op2::Sub, x, y) => {
+                    std::cmp::max(degrees[*x], degrees[*y])
+                }
+                FlatNode::Pow(x, p) => p * degrees[*x],
+            };
+            degrees.push(deg);
+        }
+        degrees.last().copied().unwrap_or(0)
+    }
+
+    /// For each node, the index of the last node that uses it as an operand.
+    fn last_uses(&self) -> Vec<usize> {
+        let mut last_uses: Vec<usize> = (0..self.nodes.len()).collect();
+        for (i, node) in self.nodes.iter().enumerate() {
+            match node {
+                FlatNode::Double(x) | FlatNode::Square(x) | FlatNode::Pow(x, _) => {
+                    last_uses[*x] = i
+                }
+                FlatNode::BinOp(_, x, y) => {
+                    last_uses[*x] = i;
+                    last_uses[*y] = i;
+                }
+                _ => (),
+            }
+        }
+        last_uses
+    }
+}
+
+/// Borrows the result of an earlier node of a [FlatExpr].
+fn borrow_result<'b, F: FftField>(
+    results: &'b [Option<EvalResult<'_, F>>],
+    i: usize,
+) -> EvalResult<'b, F> {
+    match results[i].as_ref().expect("result of the node was dropped") {
+        EvalResult::Constant(x) => EvalResult::Constant(*x),
+        EvalResult::SubEvals {
+            domain,
+            shift,
+            evals,
+        } => EvalResult::SubEvals {
+            domain: *domain,
+            shift: *shift,
+            evals,
+        },
+        EvalResult::Evals { domain, evals } => EvalResult::SubEvals {
+            domain: *domain,
+            shift: 0,
+            evals,
+        },
+    }
+}
+
+impl<F: FftField> FlatExpr<F> {
+    /// Compute the polynomial corresponding to this expression, in evaluation form.
+    /// The nodes are evaluated in order, and the result of a node is dropped
+    /// (or reused in place) as soon as its last user is evaluated.
+    /// It panics if the expression has a [Parameter](Expr::Parameter) that was not bound
+    /// with [Expr::bind_parameters].
+    pub fn evaluations<'a>(&self, env: &Environment<'a, F>) -> Evaluations<F, D<F>> {
+        let d1_size = env.domain.d1.size;
+        let d = evaluations_domain(self.degree(d1_size), d1_size);
+        let dom = (d, get_domain(d, env));
+
+        let last_uses = self.last_uses();
+        let mut results: Vec<Option<EvalResult<'a, F>>> = Vec::with_capacity(self.nodes.len());
+
+        for (i, node) in self.nodes.iter().enumerate() {
+            // an operand used for the last time can be consumed, unless it is used twice by this node
+            let owned = |results: &mut Vec<Option<EvalResult<'a, F>>>, x: usize, other: usize| {
+                if last_uses[x] == i && x != other {
+                    results[x].take()
+                } else {
+                    None
+                }
+            };
+
+            let res: EvalResult<'a, F> = match node {
+                FlatNode::Constant(x) => EvalResult::Constant(*x),
+                FlatNode::Parameter(name) => panic!("parameter {name} is not bound"),
+                FlatNode::VanishesOnLast4Rows => EvalResult::SubEvals {
+                    domain: Domain::D8,
+                    shift: 0,
+                    evals: env.vanishes_on_last_4_rows,
+                },
+                FlatNode::UnnormalizedLagrangeBasis(j) => EvalResult::Evals {
+                    domain: d,
+                    evals: unnormalized_lagrange_evals(env.l0_1, *j, d, env),
+                },
+                FlatNode::Cell(Variable { col, row }) => match env.column(col) {
+                    None => EvalResult::Constant(F::zero()),
+                    Some(evals) => EvalResult::SubEvals {
+                        domain: col.domain(),
+                        shift: row.shift(),
+                        evals,
+                    },
+                },
+                FlatNode::Double(x) => match owned(&mut results, *x, usize::MAX) {
+                    Some(EvalResult::Evals { domain, mut evals }) => {
+                        evals.evals.par_iter_mut().for_each(|x| {
+                            x.double_in_place();
+                        });
+                        EvalResult::Evals { domain, evals }
+                    }
+                    Some(x) => x.clone().add(x, dom),
+                    None => borrow_result(&results, *x).add(borrow_result(&results, *x), dom),
+                },
+                FlatNode::Square(x) => match owned(&mut results, *x, usize::MAX) {
+                    Some(x) => x.square(dom),
+                    None => borrow_result(&results, *x).square(dom),
+                },
+                FlatNode::Pow(x, p) => match owned(&mut results, *x, usize::MAX) {
+                    Some(x) => x.pow(*p, dom),
+                    None => borrow_result(&results, *x).pow(*p, dom),
+                },
+                FlatNode::BinOp(op, x, y) => {
+                    let owned_x = owned(&mut results, *x, *y);
+                    let owned_y = owned(&mut results, *y, *x);
+                    let x = owned_x.unwrap_or_else(|| borrow_result(&results, *x));
+                    let y = owned_y.unwrap_or_else(|| borrow_result(&results, *y));
+                    match op {
+                        Op2::Mul => x.mul(y, dom),
+                        Op2::Add => x.add(y, dom),
+                        Op2::Sub => x.sub(y, dom),
+                    }
+                }
+            };
+
+            // drop the operands that are not used anymore
+            match node {
+                FlatNode::Double(x) | FlatNode::Square(x) | FlatNode::Pow(x, _) => {
+                    if last_uses[*x] == i {
+                        results[*x] = None;
+                    }
+                }
+                FlatNode::BinOp(_, x, y) => {
+                    for x in [x, y] {
+                        if last_uses[*x] == i {
+                            results[*x] = None;
+                        }
+                    }
+                }
+                _ => (),
+            }
+
+            results.push(Some(res));
+        }
+
+        match results.pop().flatten() {
+            Some(res) => res.into_evaluations(d, env),
+            None => EvalResult::init_(dom, |_| F::zero()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// A "linearization", which is linear combination with `E` coefficients of
+/// columns.
+pub struct Linearization<E> {
+    pub constant_term: E,
+    pub index_terms: Vec<(Column, E)>,
+}
+
+impl<E: Default> Default for Linearization<E> {
+    fn default() -> Self {
+        Linearization {
+            constant_term: E::default(),
+            index_terms: vec![],
+        }
+    }
+}
 
 impl<A> Linearization<A> {
     /// Apply a function to all the coefficients in the linearization.
@@ -1510,6 +2469,18 @@ impl<A> Linearization<A> {
     }
 }
 
+impl<C: Clone + Zero + One + PartialEq> Linearization<Expr<C>> {
+    /// Rebuilds the expression that was linearized,
+    /// `constant_term + sum_i col_i * index_term_i`.
+    pub fn to_expr(&self) -> Expr<C> {
+        self.index_terms
+            .iter()
+            .fold(self.constant_term.clone(), |acc, (col, term)| {
+                acc + Expr::cell(*col, Curr) * term.clone()
+            })
+    }
+}
+
 impl<F: FftField> Linearization<Expr<ConstantExpr<F>>> {
     /// Evaluate the constants in a linearization with `ConstantExpr<F>` coefficients down
     /// to literal field elements.
@@ -1533,7 +2504,7 @@ impl<F: FftField> Linearization<Vec<PolishToken<F>>> {
         self.index_terms.iter().for_each(|(idx, c)| {
             let c = PolishToken::evaluate(c, env.domain.d1, pt, evals, cs).unwrap();
             let e = env
-                .get_column(idx)
+                .column(idx)
                 .unwrap_or_else(|| panic!("Index polynomial {:?} not found", idx));
             let scale = e.evals.len() / n;
             res.par_iter_mut()
@@ -1548,6 +2519,28 @@ impl<F: FftField> Linearization<Vec<PolishToken<F>>> {
     }
 }
 
+/// Given a linearization, compute the commitment to its index terms,
+/// that is the sum of `coeff * comms[col]` over all `(col, coeff)` in `lin.index_terms`,
+/// where each coefficient is evaluated at `pt` using the proof evaluations and the constants.
+/// The constant term of the linearization is not part of the result.
+pub fn linearization_commitment<G: CommitmentCurve>(
+    lin: &Linearization<Vec<PolishToken<G::ScalarField>>>,
+    d: D<G::ScalarField>,
+    pt: G::ScalarField,
+    evals: &[ProofEvaluations<G::ScalarField>],
+    c: &Constants<G::ScalarField>,
+    comms: &HashMap<Column, &PolyComm<G>>,
+) -> Result<PolyComm<G>, ExprError> {
+    let mut commitments = Vec::with_capacity(lin.index_terms.len());
+    let mut scalars = Vec::with_capacity(lin.index_terms.len());
+    for (col, tokens) in &lin.index_terms {
+        let comm = comms.get(col).ok_or(ExprError::MissingCommitment(*col))?;
+        scalars.push(PolishToken::evaluate(tokens, d, pt, evals, c)?);
+        commitments.push(*comm);
+    }
+    Ok(PolyComm::multi_scalar_mul(&commitments, &scalars))
+}
+
 impl<F: FftField> Linearization<Expr<ConstantExpr<F>>> {
     /// Given a linearization and an environment, compute the polynomial corresponding to the
     /// linearization, in evaluation form.
@@ -1563,7 +2556,7 @@ impl<F: FftField> Linearization<Expr<ConstantExpr<F>>> {
         self.index_terms.iter().for_each(|(idx, c)| {
             let c = c.evaluate_(env.domain.d1, pt, evals, cs).unwrap();
             let e = env
-                .get_column(idx)
+                .column(idx)
                 .unwrap_or_else(|| panic!("Index polynomial {:?} not found", idx));
             let scale = e.evals.len() / n;
             res.par_iter_mut()
@@ -1629,6 +2622,36 @@ impl<F: Neg<Output = F> + Clone + One + Zero + PartialEq> Expr<F> {
             VanishesOnLast4Rows => true,
             UnnormalizedLagrangeBasis(_) => true,
             Cache(_, x) => x.is_constant(evaluated),
+            Parameter(_) => true,
+        }
+    }
+
+    /// Pushes the negations of the expression down to its leaves:
+    /// every `a - b` is rewritten as `a + (-b)` and the negation is distributed over
+    /// additions and multiplications, until it reaches a constant
+    /// (which is negated) or another leaf (which gets a `-1` coefficient).
+    /// The resulting expression has no subtraction node.
+    pub fn push_neg(&self) -> Self {
+        self.push_neg_(false)
+    }
+
+    fn push_neg_(&self, neg: bool) -> Self {
+        use Expr::*;
+        let leaf = |e: Self| if neg { -e } else { e };
+        match self {
+            BinOp(Op2::Add, x, y) => x.push_neg_(neg) + y.push_neg_(neg),
+            BinOp(Op2::Sub, x, y) => x.push_neg_(neg) + y.push_neg_(!neg),
+            BinOp(Op2::Mul, x, y) => x.push_neg_(neg) * y.push_neg_(false),
+            Double(x) => Double(Box::new(x.push_neg_(neg))),
+            Square(x) => leaf(Square(Box::new(x.push_neg_(false)))),
+            Pow(x, p) => leaf(Pow(Box::new(x.push_neg_(false)), *p)),
+            // the cached expression is shared, so the negation stays outside of it
+            Cache(id, x) => leaf(Cache(*id, Box::new(x.push_neg_(false)))),
+            Constant(_)
+            | Cell(_)
+            | VanishesOnLast4Rows
+            | UnnormalizedLagrangeBasis(_)
+            | Parameter(_) => leaf(self.clone()),
         }
     }
 
@@ -1672,6 +2695,7 @@ impl<F: Neg<Output = F> + Clone + One + Zero + PartialEq> Expr<F> {
             Cache(_, e) => e.monomials(ev),
             UnnormalizedLagrangeBasis(i) => constant(UnnormalizedLagrangeBasis(*i)),
             VanishesOnLast4Rows => constant(VanishesOnLast4Rows),
+            Parameter(name) => constant(Parameter(name.clone())),
             Constant(c) => constant(Constant(c.clone())),
             Cell(var) => sing(vec![*var], Constant(F::one())),
             BinOp(Op2::Add, e1, e2) => {
@@ -1708,6 +2732,27 @@ impl<F: Neg<Output = F> + Clone + One + Zero + PartialEq> Expr<F> {
         }
     }
 
+    /// Returns the coefficient of `monomial` in the expression, seen as a polynomial in the columns of `monomial`
+    /// whose coefficients are polynomials in the other columns (see [Expr::linearize]).
+    /// For example, the coefficient of `w0 * w1` in `q_m * w0 * w1 + w2` is `q_m`.
+    /// The variables of `monomial` can be given in any order,
+    /// and the coefficient is zero if the monomial does not appear in the expression.
+    pub fn coefficient_of(&self, monomial: &[Variable]) -> Self {
+        let mut monomial = monomial.to_vec();
+        monomial.sort();
+        let columns: HashSet<Column> = monomial.iter().map(|v| v.col).collect();
+
+        let mut res = Self::zero();
+        for (m, c) in self.monomials(&HashSet::new()) {
+            let (m, others): (Vec<_>, Vec<_>) =
+                m.into_iter().partition(|v| columns.contains(&v.col));
+            if m == monomial {
+                res += others.into_iter().fold(c, |acc, v| acc * Expr::Cell(v));
+            }
+        }
+        res
+    }
+
     /// There is an optimization in PLONK called "linearization" in which a certain
     /// polynomial is expressed as a linear combination of other polynomials in order
     /// to reduce the number of evaluations needed in the IOP (by relying on the homomorphic
@@ -1777,9 +2822,13 @@ impl<F: Neg<Output = F> + Clone + One + Zero + PartialEq> Expr<F> {
                 return Err(ExprError::FailedLinearization);
             }
         }
+        // HashMap deliberately uses an unstable order; here we sort to ensure that the output is
+        // deterministic.
+        let mut index_terms: Vec<_> = res.into_iter().collect();
+        index_terms.sort_by(|(x, _), (y, _)| x.cmp(y));
         Ok(Linearization {
             constant_term,
-            index_terms: res.into_iter().collect(),
+            index_terms,
         })
     }
 }
@@ -1967,12 +3016,15 @@ where
     }
 }
 
-impl<F: Zero> Sub<Expr<F>> for Expr<F> {
+impl<F: Zero + One + Neg<Output = F>> Sub<Expr<F>> for Expr<F> {
     type Output = Expr<F>;
     fn sub(self, other: Self) -> Self {
         if other.is_zero() {
             return self;
         }
+        if self.is_zero() {
+            return -other;
+        }
         Expr::BinOp(Op2::Sub, Box::new(self), Box::new(other))
     }
 }
@@ -1989,6 +3041,12 @@ impl<F: Field> From<u64> for Expr<ConstantExpr<F>> {
     }
 }
 
+impl<F: PrimeField> From<BigUint> for Expr<ConstantExpr<F>> {
+    fn from(x: BigUint) -> Self {
+        constant_from_bigint(&x.to_bytes_le())
+    }
+}
+
 impl<F: Field> From<u64> for ConstantExpr<F> {
     fn from(x: u64) -> Self {
         ConstantExpr::Literal(F::from(x))
@@ -2093,6 +3151,7 @@ where
                 cache.insert(*id, e.as_ref().clone());
                 id.var_name()
             }
+            Parameter(name) => format!("parameter({name})"),
         }
     }
 
@@ -2134,6 +3193,40 @@ where
                 cache.insert(*id, e.as_ref().clone());
                 id.latex_name()
             }
+            Parameter(name) => format!("\\mathsf{{{name}}}"),
+        }
+    }
+
+    /// Converts the expression to plain text, for diagnostics.
+    /// Witness columns are rendered with their name in `column_names`, if they have one,
+    /// and next-row variables are marked with a `'`.
+    pub fn text_str(&self, column_names: &HashMap<usize, String>) -> String {
+        use Expr::*;
+        match self {
+            Double(x) => format!("2 * {}", x.text_str(column_names)),
+            Constant(x) => x.ocaml(),
+            Cell(v) => v.text(column_names),
+            UnnormalizedLagrangeBasis(i) => format!("unnormalized_lagrange_basis({})", *i),
+            VanishesOnLast4Rows => "vanishes_on_last_4_rows".to_string(),
+            BinOp(Op2::Add, x, y) => format!(
+                "({} + {})",
+                x.text_str(column_names),
+                y.text_str(column_names)
+            ),
+            BinOp(Op2::Mul, x, y) => format!(
+                "({} * {})",
+                x.text_str(column_names),
+                y.text_str(column_names)
+            ),
+            BinOp(Op2::Sub, x, y) => format!(
+                "({} - {})",
+                x.text_str(column_names),
+                y.text_str(column_names)
+            ),
+            Pow(x, d) => format!("{}^{d}", x.text_str(column_names)),
+            Square(x) => format!("{}^2", x.text_str(column_names)),
+            Cache(_, e) => e.text_str(column_names),
+            Parameter(name) => name.clone(),
         }
     }
 }
@@ -2170,11 +3263,41 @@ pub fn constant<F>(x: F) -> E<F> {
     Expr::Constant(ConstantExpr::Literal(x))
 }
 
+/// An integer constant given by its little-endian bytes, reduced modulo the field's modulus.
+/// This is convenient for constants that don't fit in a `u64`.
+pub fn constant_from_bigint<F: PrimeField>(bytes: &[u8]) -> E<F> {
+    constant(F::from_le_bytes_mod_order(bytes))
+}
+
+/// Convenience function to create a named parameter as [Expr].
+pub fn parameter<F>(name: &str) -> E<F> {
+    Expr::Parameter(name.to_string())
+}
+
 /// Helper function to quickly create an expression for a witness.
 pub fn witness<F>(i: usize, row: CurrOrNext) -> E<F> {
     E::<F>::cell(Column::Witness(i), row)
 }
 
+/// Checks at compile time that `I` is a valid witness column.
+struct WitnessColumn<const I: usize>;
+
+impl<const I: usize> WitnessColumn<I> {
+    const IN_BOUNDS: () = assert!(I < COLUMNS, "witness column out of bounds");
+}
+
+/// Creates the variable for the witness column `I` at the given row.
+/// Unlike [witness], the column is checked at compile time:
+/// `witness_var::<15>(Curr)` does not compile.
+pub fn witness_var<const I: usize>(row: CurrOrNext) -> Variable {
+    #[allow(clippy::let_unit_value)]
+    let () = WitnessColumn::<I>::IN_BOUNDS;
+    Variable {
+        col: Column::Witness(I),
+        row,
+    }
+}
+
 /// Same as [witness] but for the current row.
 pub fn witness_curr<F>(i: usize) -> E<F> {
     witness(i, CurrOrNext::Curr)
@@ -2194,14 +3317,32 @@ pub fn coeff<F>(i: usize) -> E<F> {
     E::<F>::cell(Column::Coefficient(i), CurrOrNext::Curr)
 }
 
+/// The constraint `then_` if `selector` is 1, and `else_` if it is 0:
+/// `selector * then_ + (1 - selector) * else_`.
+/// Constraining `selector` to be a boolean is left to the caller.
+pub fn if_then_else<F: Field>(selector: E<F>, then_: E<F>, else_: E<F>) -> E<F> {
+    selector.clone() * then_ + (E::one() - selector) * else_
+}
+
+/// The constraint `constraint` if `selector` is 1, and no constraint if it is 0:
+/// `selector * constraint`.
+/// Constraining `selector` to be a boolean is left to the caller.
+pub fn when<F: Field>(selector: E<F>, constraint: E<F>) -> E<F> {
+    selector * constraint
+}
+
 /// You can import this module like `use kimchi::circuits::expr::prologue::*` to obtain a number of handy aliases and helpers
 pub mod prologue {
-    pub use super::{coeff, constant, index, witness, witness_curr, witness_next, E};
+    pub use super::{
+        coeff, constant, if_then_else, index, parameter, when, witness, witness_curr, witness_next,
+        witness_var, E,
+    };
 }
 
 #[cfg(test)]
 pub mod test {
     use super::*;
+    use crate::circuits::polynomial::WitnessOverDomains;
     use crate::circuits::{
         constraints::ConstraintSystem,
         gate::CircuitGate,
@@ -2209,9 +3350,17 @@ pub mod test {
         wires::Wire,
     };
     use ark_ff::UniformRand;
+    use ark_poly::{Polynomial, UVPolynomial};
     use array_init::array_init;
-    use mina_curves::pasta::fp::Fp;
+    use commitment_dlog::srs::SRS;
+    use mina_curves::pasta::{fp::Fp, vesta::Affine};
+    use proptest::{prop_assert_eq, proptest, test_runner::TestCaseError};
     use rand::{prelude::StdRng, SeedableRng};
+    use std::{
+        cmp::Ordering,
+        collections::hash_map::DefaultHasher,
+        hash::{Hash, Hasher},
+    };
 
     #[test]
     #[should_panic]
@@ -2226,6 +3375,84 @@ pub mod test {
         expr.linearize(evaluated).unwrap();
     }
 
+    #[test]
+    fn test_linearize_index_terms_order() {
+        // coeff(2) * w0 + Poseidon * w1 + coeff(0) * w2 + CompleteAdd * w3
+        let expr: E<Fp> = coeff(2) * witness_curr(0)
+            + index(GateType::Poseidon) * witness_curr(1)
+            + coeff(0) * witness_curr(2)
+            + index(GateType::CompleteAdd) * witness_curr(3);
+        let evaluated: HashSet<_> = (0..COLUMNS).map(Column::Witness).collect();
+
+        let columns = |expr: &E<Fp>| -> Vec<Column> {
+            expr.linearize(evaluated.clone())
+                .unwrap()
+                .index_terms
+                .into_iter()
+                .map(|(col, _)| col)
+                .collect()
+        };
+
+        let expected = vec![
+            Column::Index(GateType::Poseidon),
+            Column::Index(GateType::CompleteAdd),
+            Column::Coefficient(0),
+            Column::Coefficient(2),
+        ];
+        for _ in 0..10 {
+            assert_eq!(columns(&expr), expected);
+        }
+    }
+
+    #[test]
+    fn test_linearization_commitment() {
+        let rng = &mut StdRng::from_seed([0u8; 32]);
+        let srs = SRS::<Affine>::create(8);
+        let domain = D::<Fp>::new(8).unwrap();
+        let pt = Fp::rand(rng);
+        let constants = Constants {
+            alpha: Fp::rand(rng),
+            beta: Fp::rand(rng),
+            gamma: Fp::rand(rng),
+            joint_combiner: None,
+            endo_coefficient: Fp::rand(rng),
+            mds: vec![vec![]],
+        };
+
+        // alpha * coeff(0) + 3 * Poseidon
+        let lin = Linearization {
+            constant_term: vec![],
+            index_terms: vec![
+                (Column::Coefficient(0), vec![PolishToken::Alpha]),
+                (
+                    Column::Index(GateType::Poseidon),
+                    vec![PolishToken::Literal(Fp::from(3u64))],
+                ),
+            ],
+        };
+        let p0 = DensePolynomial::<Fp>::rand(7, rng);
+        let p1 = DensePolynomial::<Fp>::rand(7, rng);
+        let c0 = srs.commit_non_hiding(&p0, None);
+        let c1 = srs.commit_non_hiding(&p1, None);
+        let mut comms = HashMap::new();
+        comms.insert(Column::Coefficient(0), &c0);
+        comms.insert(Column::Index(GateType::Poseidon), &c1);
+
+        let res = linearization_commitment(&lin, domain, pt, &[], &constants, &comms).unwrap();
+        let expected = &(&c0 * constants.alpha) + &(&c1 * Fp::from(3u64));
+        assert_eq!(res.unshifted, expected.unshifted);
+
+        // a commitment for every index term is required
+        comms.remove(&Column::Index(GateType::Poseidon));
+        let res = linearization_commitment(&lin, domain, pt, &[], &constants, &comms);
+        assert!(matches!(
+            res,
+            Err(ExprError::MissingCommitment(Column::Index(
+                GateType::Poseidon
+            )))
+        ));
+    }
+
     #[test]
     #[should_panic]
     fn test_degree_tracking() {
@@ -2273,12 +3500,941 @@ pub mod test {
             domain: constraint_system.domain,
             index: HashMap::new(),
             lookup: None,
+            column_names: HashMap::new(),
         };
 
         // this should panic as we don't have a domain large enough
         expr.evaluations(&env);
     }
 
+    /// A small circuit of two generic gates
+    fn test_constraint_system() -> ConstraintSystem<Fp> {
+        let one = Fp::from(1u32);
+        let gates = vec![
+            CircuitGate::create_generic_gadget(Wire::new(0), GenericGateSpec::Const(one), None),
+            CircuitGate::create_generic_gadget(Wire::new(1), GenericGateSpec::Const(one), None),
+        ];
+        ConstraintSystem::fp_for_testing(gates)
+    }
+
+    /// An environment for `cs` with the witness evaluations `domain_evals`
+    fn test_env<'a>(
+        cs: &'a ConstraintSystem<Fp>,
+        domain_evals: &'a WitnessOverDomains<Fp>,
+    ) -> Environment<'a, Fp> {
+        let one = Fp::from(1u32);
+        let constants = Constants {
+            alpha: one,
+            beta: one,
+            gamma: one,
+            joint_combiner: None,
+            endo_coefficient: one,
+            mds: vec![vec![]],
+        };
+        cs.fixed_environment().with_witness(
+            &domain_evals.d8.this.w,
+            &domain_evals.d8.this.z,
+            constants,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_all_zero() {
+        let constraint_system = test_constraint_system();
+
+        let witness_cols: [_; COLUMNS] = array_init(|_| DensePolynomial::zero());
+        let permutation = DensePolynomial::zero();
+        let domain_evals = constraint_system.evaluate(&witness_cols, &permutation);
+        let env = test_env(&constraint_system, &domain_evals);
+
+        // the witness is all zeros, so these constraints hold everywhere
+        let satisfied: Vec<E<Fp>> = vec![
+            witness_curr(0),
+            witness_curr(1) * witness_next(2),
+            witness_curr(3).square() - witness_next(3),
+        ];
+        assert_eq!(E::all_zero(&satisfied, &env), Ok(()));
+
+        // a constant constraint fails on the very first row
+        let mut violated = satisfied;
+        violated.push(constant(Fp::from(1u32)));
+        assert_eq!(E::all_zero(&violated, &env), Err((3, 0)));
+    }
+
+    #[test]
+    fn test_evaluate_monomials() {
+        let constraint_system = test_constraint_system();
+
+        // the witness column i is the constant polynomial i
+        let witness_cols: [_; COLUMNS] =
+            array_init(|i| DensePolynomial::from_coefficients_vec(vec![Fp::from(i as u64)]));
+        let permutation = DensePolynomial::zero();
+        let domain_evals = constraint_system.evaluate(&witness_cols, &permutation);
+        let env = test_env(&constraint_system, &domain_evals);
+
+        // 2 * w3 * w4' + 5 * w1 - w0
+        let expr: E<Fp> = constant(Fp::from(2u32)) * witness_curr(3) * witness_next(4)
+            + constant(Fp::from(5u32)) * witness_curr(1)
+            - witness_curr(0);
+
+        let curr = |i| Variable {
+            col: Column::Witness(i),
+            row: Curr,
+        };
+        let next = |i| Variable {
+            col: Column::Witness(i),
+            row: Next,
+        };
+        for row in [0, 3] {
+            let monomials = expr.evaluate_monomials(&env, row).unwrap();
+            assert_eq!(
+                monomials,
+                vec![
+                    (vec![curr(0)], Fp::zero()),
+                    (vec![curr(1)], Fp::from(5u32)),
+                    (vec![curr(3), next(4)], Fp::from(24u32)),
+                ]
+            );
+        }
+
+        // the test circuit does not use lookups
+        let missing: E<Fp> = E::cell(Column::LookupAggreg, Curr) * witness_curr(0);
+        assert!(matches!(
+            missing.evaluate_monomials(&env, 0),
+            Err(ExprError::MissingColumn(Column::LookupAggreg, _))
+        ));
+    }
+
+    #[test]
+    fn test_combine_constraints_horner() {
+        let rng = &mut StdRng::from_seed([0u8; 32]);
+        let constants = Constants {
+            alpha: Fp::rand(rng),
+            beta: Fp::rand(rng),
+            gamma: Fp::rand(rng),
+            joint_combiner: None,
+            endo_coefficient: Fp::rand(rng),
+            mds: vec![vec![]],
+        };
+        let evals = [
+            ProofEvaluations::dummy_with_witness_evaluations(array_init(|_| Fp::rand(rng))),
+            ProofEvaluations::dummy_with_witness_evaluations(array_init(|_| Fp::rand(rng))),
+        ];
+        let d = D::new(8).unwrap();
+        let pt = Fp::rand(rng);
+
+        let cs: Vec<E<Fp>> = vec![
+            witness_curr(0) * witness_next(1),
+            witness_curr(2).square() - witness_curr(3),
+            witness_next(4) + constant(Fp::from(7u32)),
+            witness_curr(5),
+        ];
+        for alpha0 in [0, 3] {
+            let naive = E::combine_constraints(alpha0..alpha0 + cs.len() as u32, cs.clone());
+            let horner = E::combine_constraints_horner(alpha0, cs.clone());
+            assert_eq!(
+                horner.evaluate_(d, pt, &evals, &constants).unwrap(),
+                naive.evaluate_(d, pt, &evals, &constants).unwrap()
+            );
+        }
+
+        assert_eq!(E::<Fp>::combine_constraints_horner(2, vec![]), E::zero());
+    }
+
+    #[test]
+    fn test_text_str_column_names() {
+        let expr: E<Fp> = witness_curr(0) * witness_next(3) - witness_curr(1).square();
+
+        let mut names = HashMap::new();
+        assert_eq!(expr.text_str(&names), "((w0 * w3') - w1^2)");
+
+        names.insert(0, "x".to_string());
+        names.insert(3, "y".to_string());
+        assert_eq!(expr.text_str(&names), "((x * y') - w1^2)");
+    }
+
+    #[test]
+    fn test_environment_columns() {
+        let constraint_system = test_constraint_system();
+
+        let witness_cols: [_; COLUMNS] =
+            array_init(|i| DensePolynomial::from_coefficients_vec(vec![Fp::from(i as u64)]));
+        let permutation = DensePolynomial::zero();
+        let domain_evals = constraint_system.evaluate(&witness_cols, &permutation);
+        let env = test_env(&constraint_system, &domain_evals);
+
+        assert_eq!(env.witness_columns().count(), COLUMNS);
+        for (i, w) in env.witness_columns().enumerate() {
+            assert_eq!(w.evals[0], Fp::from(i as u64));
+            assert_eq!(env.column(&Column::Witness(i)).unwrap().evals, w.evals);
+        }
+        assert!(env.column(&Column::Z).is_some());
+        assert!(env.column(&Column::LookupAggreg).is_none());
+        assert!(env.column(&Column::Index(GateType::Poseidon)).is_some());
+        assert!(env.column(&Column::Index(GateType::ChaCha0)).is_none());
+    }
+
+    #[test]
+    fn test_environment_validate() {
+        let constraint_system = test_constraint_system();
+
+        let witness_cols: [_; COLUMNS] = array_init(|_| DensePolynomial::zero());
+        let permutation = DensePolynomial::zero();
+        let domain_evals = constraint_system.evaluate(&witness_cols, &permutation);
+        let mut env = test_env(&constraint_system, &domain_evals);
+        env.validate().unwrap();
+
+        // the witness is expected over d8
+        env.witness = &domain_evals.d4.this.w;
+        let d4_size = constraint_system.domain.d4.size();
+        let d8_size = constraint_system.domain.d8.size();
+        assert!(matches!(
+            env.validate(),
+            Err(ExprError::WrongEvaluationsSize(_, got, expected)) if got == d4_size && expected == d8_size
+        ));
+    }
+
+    #[test]
+    fn test_witness_var() {
+        assert_eq!(
+            witness_var::<3>(Curr),
+            Variable {
+                col: Column::Witness(3),
+                row: Curr
+            }
+        );
+        assert_eq!(E::<Fp>::w_curr::<3>(), witness_curr(3));
+        assert_eq!(E::<Fp>::w_next::<14>(), witness_next(14));
+    }
+
+    #[test]
+    fn test_evaluations_on_subdomain() {
+        let constraint_system = test_constraint_system();
+        let rng = &mut StdRng::from_seed([0u8; 32]);
+
+        let n = constraint_system.domain.d1.size();
+        let witness_cols: [_; COLUMNS] = array_init(|_| DensePolynomial::rand(n - 1, rng));
+        let permutation = DensePolynomial::zero();
+        let domain_evals = constraint_system.evaluate(&witness_cols, &permutation);
+        let env = test_env(&constraint_system, &domain_evals);
+
+        // degree 2n - 2, naturally evaluated over d4
+        let expr: E<Fp> = witness_curr(0) * witness_curr(1) + witness_curr(2);
+        let poly = &(&witness_cols[0] * &witness_cols[1]) + &witness_cols[2];
+
+        for sub in [
+            constraint_system.domain.d1,
+            constraint_system.domain.d2,
+            constraint_system.domain.d8,
+        ] {
+            let evals = expr.evaluations_on_subdomain(&env, sub);
+            assert_eq!(evals.domain(), sub);
+            for (x, eval) in sub.elements().zip(&evals.evals) {
+                assert_eq!(poly.evaluate(&x), *eval);
+            }
+        }
+    }
+
+    #[test]
+    fn test_flat_expr_evaluations() {
+        let constraint_system = test_constraint_system();
+        let rng = &mut StdRng::from_seed([0u8; 32]);
+
+        let n = constraint_system.domain.d1.size();
+        let witness_cols: [_; COLUMNS] = array_init(|_| DensePolynomial::rand(n - 1, rng));
+        let permutation = DensePolynomial::zero();
+        let domain_evals = constraint_system.evaluate(&witness_cols, &permutation);
+        let env = test_env(&constraint_system, &domain_evals);
+
+        let mut cache = Cache::default();
+        let x = cache.cache(witness_curr(0) * witness_next(1));
+        let y: E<Fp> = witness_curr(2).double();
+        let expr: E<Fp> = x.clone() * x.clone()
+            + (y.clone() - witness_curr(3).square()) * y
+            + x
+            + witness_curr(4).pow(3)
+            + E::VanishesOnLast4Rows * witness_curr(5);
+
+        let flat = expr.evaluate_constants(&env).flatten();
+        assert_eq!(flat.evaluations(&env).evals, expr.evaluations(&env).evals);
+
+        // the cached and the repeated sub-expressions are stored once
+        let count = |f: fn(&FlatNode<Fp>) -> bool| flat.nodes.iter().filter(|n| f(n)).count();
+        assert_eq!(count(|n| matches!(n, FlatNode::Double(_))), 1);
+        assert_eq!(
+            count(|n| matches!(n, FlatNode::BinOp(Op2::Mul, x, y) if x == y)),
+            1
+        );
+    }
+
+    #[test]
+    fn test_push_neg() {
+        fn sub_count<C>(e: &Expr<C>) -> usize {
+            match e {
+                Expr::BinOp(op, x, y) => usize::from(*op == Op2::Sub) + sub_count(x) + sub_count(y),
+                Expr::Double(x) | Expr::Square(x) | Expr::Pow(x, _) | Expr::Cache(_, x) => {
+                    sub_count(x)
+                }
+                _ => 0,
+            }
+        }
+
+        let constraint_system = test_constraint_system();
+        let rng = &mut StdRng::from_seed([0u8; 32]);
+
+        let n = constraint_system.domain.d1.size();
+        let witness_cols: [_; COLUMNS] = array_init(|_| DensePolynomial::rand(n - 1, rng));
+        let permutation = DensePolynomial::zero();
+        let domain_evals = constraint_system.evaluate(&witness_cols, &permutation);
+        let env = test_env(&constraint_system, &domain_evals);
+
+        // -((w0 - 2 * w1) * (3 - w2')) - (w3 - w4^2)^2
+        let mut cache = Cache::default();
+        let x = cache.cache(witness_curr(3) - witness_curr(4).square());
+        let expr: E<Fp> = E::BinOp(
+            Op2::Sub,
+            Box::new(E::zero()),
+            Box::new(
+                (witness_curr(0) - witness_curr(1).double()) * (E::from(3u64) - witness_next(2)),
+            ),
+        ) - x.pow(2);
+        assert_eq!(sub_count(&expr), 5);
+
+        let pushed = expr.push_neg();
+        assert_eq!(sub_count(&pushed), 0);
+        assert_eq!(pushed.evaluations(&env).evals, expr.evaluations(&env).evals);
+    }
+
+    #[test]
+    fn test_sub_upsampled() {
+        let constraint_system = test_constraint_system();
+        let rng = &mut StdRng::from_seed([0u8; 32]);
+
+        let domain = constraint_system.domain;
+        let n = domain.d1.size();
+        let p = DensePolynomial::<Fp>::rand(n - 1, rng);
+        let q = DensePolynomial::<Fp>::rand(n - 1, rng);
+        let p4 = p.evaluate_over_domain_by_ref(domain.d4);
+        let q8 = q.evaluate_over_domain_by_ref(domain.d8);
+        let expected = &p - &q;
+
+        // upsampling p
+        let res = sub_upsampled(p4.clone(), q8.clone(), domain.d8);
+        assert_eq!(res.domain(), domain.d8);
+        assert_eq!(
+            res.evals,
+            expected.evaluate_over_domain_by_ref(domain.d8).evals
+        );
+
+        // subsampling q
+        let res = sub_upsampled(p4, q8, domain.d4);
+        assert_eq!(res.domain(), domain.d4);
+        assert_eq!(
+            res.evals,
+            expected.evaluate_over_domain_by_ref(domain.d4).evals
+        );
+    }
+
+    #[test]
+    fn test_if_then_else() {
+        let sel: E<Fp> = witness_curr(0);
+        let (a, b) = (witness_curr(1), witness_curr(2));
+        assert_eq!(
+            if_then_else(sel.clone(), a.clone(), b.clone()),
+            sel.clone() * a.clone() + (E::one() - sel.clone()) * b
+        );
+        assert_eq!(when(sel.clone(), a.clone()), sel * a);
+    }
+
+    #[test]
+    fn test_validate_against() {
+        let constraint_system = test_constraint_system();
+        let witness_cols: [_; COLUMNS] = array_init(|_| DensePolynomial::zero());
+        let permutation = DensePolynomial::zero();
+        let domain_evals = constraint_system.evaluate(&witness_cols, &permutation);
+        let env = test_env(&constraint_system, &domain_evals);
+
+        let ok: E<Fp> = witness_curr(0) * index(GateType::Generic) + E::cell(Column::Z, Next);
+        assert!(ok.validate_against(&env, true).is_ok());
+
+        // the test circuit has no ChaCha gate, and does not use lookups
+        let bad: E<Fp> =
+            witness_curr(COLUMNS) * index(GateType::ChaCha0) * E::cell(Column::LookupAggreg, Curr)
+                + witness_next(COLUMNS);
+        let missing = |strict| -> Vec<Column> {
+            bad.validate_against(&env, strict)
+                .unwrap_err()
+                .into_iter()
+                .map(|e| match e {
+                    ExprError::MissingColumn(col, _) => col,
+                    e => panic!("unexpected error {:?}", e),
+                })
+                .collect()
+        };
+        assert_eq!(
+            missing(false),
+            vec![Column::Witness(COLUMNS), Column::LookupAggreg]
+        );
+        assert_eq!(
+            missing(true),
+            vec![
+                Column::Witness(COLUMNS),
+                Column::Index(GateType::ChaCha0),
+                Column::LookupAggreg
+            ]
+        );
+
+        // the errors refer to the witness columns by their name, if they have one
+        let mut env = env;
+        env.column_names.insert(COLUMNS, "x_coordinate".to_string());
+        let messages: Vec<_> = bad
+            .validate_against(&env, false)
+            .unwrap_err()
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "the column x_coordinate is not in the environment",
+                "the column a is not in the environment"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stats() {
+        let e: E<Fp> = witness_curr(0) * witness_curr(1) + witness_curr(0).square();
+        let stats = e.stats();
+        assert_eq!(
+            stats,
+            ExprStats {
+                node_count: 6,
+                depth: 3,
+                max_degree: 2,
+                distinct_columns: 2,
+            }
+        );
+
+        // the sub-expression of a cached node is only counted once
+        let mut cache = Cache::default();
+        let cached = cache.cache(e);
+        let e = cached.clone() * cached + index(GateType::Generic);
+        assert_eq!(
+            e.stats(),
+            ExprStats {
+                node_count: 2 + 2 * 1 + stats.node_count + 1,
+                depth: 3 + stats.depth,
+                max_degree: 2 * stats.max_degree,
+                distinct_columns: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn test_univariate_in() {
+        let rng = &mut StdRng::from_seed([0u8; 32]);
+        let constants = Constants {
+            alpha: Fp::rand(rng),
+            beta: Fp::rand(rng),
+            gamma: Fp::rand(rng),
+            joint_combiner: None,
+            endo_coefficient: Fp::rand(rng),
+            mds: vec![vec![]],
+        };
+        let mut evals = [
+            ProofEvaluations::dummy_with_witness_evaluations(array_init(|_| Fp::rand(rng))),
+            ProofEvaluations::dummy_with_witness_evaluations(array_init(|_| Fp::rand(rng))),
+        ];
+        let d = D::new(8).unwrap();
+        let pt = Fp::rand(rng);
+
+        // w3 * (w3 - w1') * alpha + w3^5 - w0
+        let var = witness_var::<3>(Curr);
+        let e: E<Fp> =
+            E::Cell(var) * (E::Cell(var) - witness_next(1)) * E::Constant(ConstantExpr::Alpha)
+                + E::Cell(var).pow(5)
+                - witness_curr(0);
+        let p = e.univariate_in(var, d, pt, &evals, &constants).unwrap();
+        assert_eq!(p.degree(), 5);
+
+        // substituting a value for w3 in the polynomial or in the evaluations agrees
+        for _ in 0..3 {
+            let x = Fp::rand(rng);
+            evals[0].w[3] = x;
+            assert_eq!(
+                p.evaluate(&x),
+                e.evaluate_(d, pt, &evals, &constants).unwrap()
+            );
+        }
+
+        let e: E<Fp> = witness_curr(0) + parameter("k");
+        assert!(matches!(
+            e.univariate_in(var, d, pt, &evals, &constants),
+            Err(ExprError::UnboundParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_check_powers() {
+        let alpha = || ConstantExpr::<Fp>::Alpha;
+        let e: E<Fp> = witness_curr(0).pow(7) * E::Constant(alpha().pow(20) + alpha().pow(3));
+        e.check_powers(20).unwrap();
+        assert!(matches!(
+            e.check_powers(19),
+            Err(ExprError::PowerTooLarge(20, 19))
+        ));
+        assert!(matches!(
+            e.check_powers(6),
+            Err(ExprError::PowerTooLarge(7, 6))
+        ));
+
+        let e: E<Fp> = witness_curr(0).pow(u64::MAX);
+        assert!(matches!(
+            e.check_powers(100),
+            Err(ExprError::PowerTooLarge(u64::MAX, 100))
+        ));
+        assert!(matches!(
+            PolishToken::check_powers(&e.to_polish(), 100),
+            Err(ExprError::PowerTooLarge(u64::MAX, 100))
+        ));
+        PolishToken::check_powers(&witness_curr::<Fp>(0).pow(7).to_polish(), 100).unwrap();
+    }
+
+    #[test]
+    fn test_coefficient_of() {
+        let w = |i| Variable {
+            col: Column::Witness(i),
+            row: Curr,
+        };
+
+        // q_m * w0 * w1 + q_l * w0 + w2
+        let expr: E<Fp> = index(GateType::Generic) * coeff(0) * witness_curr(0) * witness_curr(1)
+            + coeff(1) * witness_curr(0)
+            + witness_curr(2);
+
+        // the order of the variables does not matter
+        assert_eq!(
+            expr.coefficient_of(&[w(1), w(0)]).canonicalize(),
+            (index(GateType::Generic) * coeff(0)).canonicalize()
+        );
+        assert_eq!(expr.coefficient_of(&[w(2)]), E::one());
+
+        // the coefficient of w0 contains the terms in w1
+        assert_eq!(
+            expr.coefficient_of(&[w(0)]).canonicalize(),
+            (coeff(1) + index(GateType::Generic) * coeff(0) * witness_curr(1)).canonicalize()
+        );
+
+        // absent monomials have a zero coefficient
+        assert!(expr.coefficient_of(&[w(0), w(2)]).is_zero());
+        assert!(expr.coefficient_of(&[w(3)]).is_zero());
+    }
+
+    /// Checks that `Hash` and `Ord` are consistent with `Eq`,
+    /// which `monomials` and `linearize` rely on to aggregate terms.
+    fn check_hash_eq_ord<T: Copy + Hash + Ord + std::fmt::Debug>(
+        a: T,
+        b: T,
+    ) -> Result<(), TestCaseError> {
+        let hash = |x: &T| {
+            let mut hasher = DefaultHasher::new();
+            x.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        // a copy is equal to the original, and has the same hash
+        let c = a;
+        prop_assert_eq!(a, c);
+        prop_assert_eq!(hash(&a), hash(&c));
+        prop_assert_eq!(a.cmp(&c), Ordering::Equal);
+
+        // the order is total, antisymmetric and consistent with equality
+        prop_assert_eq!(a == b, a.cmp(&b) == Ordering::Equal);
+        prop_assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+        prop_assert_eq!(a.partial_cmp(&b), Some(a.cmp(&b)));
+        if a == b {
+            prop_assert_eq!(hash(&a), hash(&b));
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn test_column_hash_eq_ord(a: Column, b: Column) {
+            check_hash_eq_ord(a, b)?;
+        }
+
+        #[test]
+        fn test_variable_hash_eq_ord(a: Variable, b: Variable) {
+            check_hash_eq_ord(a, b)?;
+            // variables in the same column only differ in their row
+            check_hash_eq_ord(a, Variable { col: a.col, row: b.row })?;
+        }
+    }
+
+    #[test]
+    fn test_to_polynomial() {
+        let constraint_system = test_constraint_system();
+        let n = constraint_system.domain.d1.size();
+        let rng = &mut StdRng::from_seed([0u8; 32]);
+
+        let witness_cols: [_; COLUMNS] = array_init(|_| DensePolynomial::rand(n - 1, rng));
+        let permutation = DensePolynomial::zero();
+        let domain_evals = constraint_system.evaluate(&witness_cols, &permutation);
+        let env = test_env(&constraint_system, &domain_evals);
+
+        let expr: E<Fp> = witness_curr(0) * witness_next(1) + witness_curr(2).square();
+        let poly = expr.to_polynomial(&env);
+
+        // the polynomial is the same as the expression on the witness polynomials
+        let pt = Fp::rand(rng);
+        let omega = constraint_system.domain.d1.group_gen;
+        let [w0, w1, w2] = [0, 1, 2].map(|i| &witness_cols[i]);
+        assert_eq!(
+            poly.evaluate(&pt),
+            w0.evaluate(&pt) * w1.evaluate(&(omega * pt)) + w2.evaluate(&pt).square()
+        );
+        assert_eq!(poly.degree(), 2 * (n - 1));
+    }
+
+    #[test]
+    fn test_domain_scale() {
+        let constraint_system = test_constraint_system();
+        let witness_cols: [_; COLUMNS] = array_init(|_| DensePolynomial::zero());
+        let permutation = DensePolynomial::zero();
+        let domain_evals = constraint_system.evaluate(&witness_cols, &permutation);
+        let env = test_env(&constraint_system, &domain_evals);
+
+        let n = env.domain.d1.size();
+        for d in Domain::all() {
+            assert_eq!(get_domain(d, &env).size(), d.scale() * n);
+        }
+    }
+
+    #[test]
+    fn test_linearization_of_gate_constraints() {
+        use crate::linearization::{constraints_expr, linearization_columns};
+
+        let constraint_system = test_constraint_system();
+        let rng = &mut StdRng::from_seed([0u8; 32]);
+
+        let n = constraint_system.domain.d1.size();
+        let witness_cols: [_; COLUMNS] = array_init(|_| DensePolynomial::rand(n - 1, rng));
+        let permutation = DensePolynomial::rand(n - 1, rng);
+        let domain_evals = constraint_system.evaluate(&witness_cols, &permutation);
+        let constants = Constants {
+            alpha: Fp::rand(rng),
+            beta: Fp::rand(rng),
+            gamma: Fp::rand(rng),
+            joint_combiner: None,
+            endo_coefficient: constraint_system.endo,
+            mds: constraint_system.fr_sponge_params.mds.clone(),
+        };
+        let env = constraint_system.fixed_environment().with_witness(
+            &domain_evals.d8.this.w,
+            &domain_evals.d8.this.z,
+            constants,
+            None,
+        );
+
+        let (expr, _) = constraints_expr::<Fp>(true, true, None);
+        expr.assert_linearization_correct(linearization_columns::<Fp>(None), &env);
+    }
+
+    #[test]
+    fn test_linearization_to_expr() {
+        let linearization: Linearization<E<Fp>> = Linearization {
+            constant_term: witness_curr(0),
+            index_terms: vec![
+                (Column::Index(GateType::Poseidon), witness_curr(1)),
+                (Column::Coefficient(2), witness_next(3)),
+            ],
+        };
+        assert_eq!(
+            linearization.to_expr(),
+            witness_curr(0)
+                + index(GateType::Poseidon) * witness_curr(1)
+                + coeff(2) * witness_next(3)
+        );
+
+        let constant: Linearization<E<Fp>> = Linearization {
+            constant_term: witness_curr(0),
+            index_terms: vec![],
+        };
+        assert_eq!(constant.to_expr(), witness_curr(0));
+    }
+
+    #[test]
+    fn test_evaluate_missing_evaluation() {
+        let d = D::<Fp>::new(8).unwrap();
+        let pt = Fp::from(2u64);
+        let evals = vec![ProofEvaluations::dummy_with_witness_evaluations(
+            [Fp::one(); COLUMNS],
+        )];
+
+        let expr: Expr<Fp> = Expr::cell(Column::Witness(0), Curr);
+        assert_eq!(expr.evaluate(d, pt, &evals).unwrap(), Fp::one());
+
+        // only the evaluations at the current row are given
+        let expr: Expr<Fp> = Expr::cell(Column::Witness(0), Next);
+        assert!(matches!(
+            expr.evaluate(d, pt, &evals),
+            Err(ExprError::MissingEvaluation(Column::Witness(0), Next))
+        ));
+
+        // the evaluations don't have lookups
+        let expr: Expr<Fp> = Expr::cell(Column::LookupSorted(0), Curr);
+        assert!(matches!(
+            expr.evaluate(d, pt, &evals),
+            Err(ExprError::MissingEvaluation(Column::LookupSorted(0), Curr))
+        ));
+
+        let expr: Expr<Fp> = Expr::cell(Column::Coefficient(0), Curr);
+        assert!(matches!(
+            expr.evaluate(d, pt, &evals),
+            Err(ExprError::MissingIndexEvaluation(Column::Coefficient(0)))
+        ));
+    }
+
+    #[test]
+    fn test_constraint_macro() {
+        let alpha = || E::<Fp>::Constant(ConstantExpr::Alpha);
+
+        let expr: E<Fp> = constraint!(w[0] * w[1] - w[2] + alpha ^ 2 * w[3]);
+        let expected =
+            witness_curr(0) * witness_curr(1) - witness_curr(2) + alpha().pow(2) * witness_curr(3);
+        assert_eq!(expr, expected);
+
+        // precedence, parentheses, literals, next row and variables
+        let x: E<Fp> = coeff(0);
+        let expr: E<Fp> = constraint!(x * (w[0] + 1) ^ 3 - w_next[1] * w[2]);
+        let expected =
+            coeff(0) * (witness_curr(0) + E::from(1u64)).pow(3) - witness_next(1) * witness_curr(2);
+        assert_eq!(expr, expected);
+    }
+
+    #[test]
+    fn test_constant_from_bigint() {
+        use o1_utils::FieldHelpers;
+
+        let constraint_system = test_constraint_system();
+        let witness_cols: [_; COLUMNS] = array_init(|_| DensePolynomial::zero());
+        let permutation = DensePolynomial::zero();
+        let domain_evals = constraint_system.evaluate(&witness_cols, &permutation);
+        let env = test_env(&constraint_system, &domain_evals);
+
+        let modulus = Fp::modulus_biguint();
+
+        let expr: E<Fp> = (modulus.clone() - 1u32).into();
+        assert_eq!(expr.evaluate_constants(&env), Expr::Constant(-Fp::one()));
+
+        // constants are reduced modulo the field's modulus
+        let expr: E<Fp> = (modulus + 5u32).into();
+        assert_eq!(
+            expr.evaluate_constants(&env),
+            Expr::Constant(Fp::from(5u32))
+        );
+
+        // 2^200 doesn't fit in a u64
+        let expr: E<Fp> = constant_from_bigint(&(BigUint::from(1u32) << 200).to_bytes_le());
+        assert_eq!(
+            expr.evaluate_constants(&env),
+            Expr::Constant(Fp::from(2u32).pow(&[200]))
+        );
+    }
+
+    #[test]
+    fn test_bind_parameters() {
+        let expr: E<Fp> = parameter("k") * witness_curr(0) + parameter("c");
+
+        let mut params = HashMap::new();
+        params.insert("k".to_string(), Fp::from(3u32));
+        params.insert("c".to_string(), Fp::from(5u32));
+        assert_eq!(
+            expr.bind_parameters(&params).unwrap(),
+            constant(Fp::from(3u32)) * witness_curr(0) + constant(Fp::from(5u32))
+        );
+
+        // instantiate the same expression with other constants
+        params.insert("k".to_string(), Fp::from(7u32));
+        assert_eq!(
+            expr.bind_parameters(&params).unwrap(),
+            constant(Fp::from(7u32)) * witness_curr(0) + constant(Fp::from(5u32))
+        );
+
+        params.remove("c");
+        assert!(matches!(
+            expr.bind_parameters(&params),
+            Err(ExprError::UnboundParameter(name)) if name == "c"
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "parameter k is not bound")]
+    fn test_to_polish_unbound_parameter() {
+        let expr: E<Fp> = parameter("k") * witness_curr(0);
+        expr.to_polish();
+    }
+
+    #[test]
+    #[should_panic(expected = "parameter k is not bound")]
+    fn test_evaluations_unbound_parameter() {
+        let constraint_system = test_constraint_system();
+        let witness_cols: [_; COLUMNS] = array_init(|_| DensePolynomial::zero());
+        let permutation = DensePolynomial::zero();
+        let domain_evals = constraint_system.evaluate(&witness_cols, &permutation);
+        let env = test_env(&constraint_system, &domain_evals);
+
+        let expr: E<Fp> = parameter("k") * witness_curr(0);
+        expr.evaluations(&env);
+    }
+
+    #[test]
+    fn test_canonicalize() {
+        let a: E<Fp> = witness_curr(0);
+        let b: E<Fp> = witness_next(1);
+        let c: E<Fp> = constant(Fp::from(3u32));
+
+        assert_eq!(
+            (a.clone() + b.clone()).canonicalize(),
+            (b.clone() + a.clone()).canonicalize()
+        );
+        assert_eq!(
+            (a.clone() * b.clone()).canonicalize(),
+            (b.clone() * a.clone()).canonicalize()
+        );
+
+        // associative chains are flattened
+        assert_eq!(
+            ((a.clone() + b.clone()) + c.clone()).canonicalize(),
+            (c.clone() + (b.clone() + a.clone())).canonicalize()
+        );
+        assert_eq!(
+            (c.clone() * (a.clone() + b.clone()) * a.clone()).canonicalize(),
+            (a.clone() * ((b.clone() + a.clone()) * c.clone())).canonicalize()
+        );
+
+        // subtraction is not commutative
+        assert_ne!(
+            (a.clone() - b.clone()).canonicalize(),
+            (b.clone() - a.clone()).canonicalize()
+        );
+        assert_eq!(
+            (a.clone() - (b.clone() * c.clone())).canonicalize(),
+            (a - (c * b)).canonicalize()
+        );
+    }
+
+    #[test]
+    fn test_sub_zero_folding() {
+        fn size<C>(e: &Expr<C>) -> usize {
+            match e {
+                Expr::Double(x) | Expr::Square(x) | Expr::Pow(x, _) | Expr::Cache(_, x) => {
+                    1 + size(x)
+                }
+                Expr::BinOp(_, x, y) => 1 + size(x) + size(y),
+                _ => 1,
+            }
+        }
+
+        let e: E<Fp> = witness_curr(0) * witness_next(1) + witness_curr(2);
+        let unfolded_right = Expr::BinOp(Op2::Sub, Box::new(e.clone()), Box::new(E::zero()));
+        let unfolded_left = Expr::BinOp(Op2::Sub, Box::new(E::zero()), Box::new(e.clone()));
+
+        let folded_right = e.clone() - E::zero();
+        let folded_left = E::zero() - e.clone();
+        assert_eq!(folded_right, e);
+        assert!(size(&folded_right) < size(&unfolded_right));
+        assert!(size(&folded_left) < size(&unfolded_left));
+
+        // a constant is simply negated
+        assert_eq!(
+            E::zero() - constant(Fp::from(3u32)),
+            constant(-Fp::from(3u32))
+        );
+
+        // the evaluations are unchanged
+        let domain = EvaluationDomains::<Fp>::create(8).unwrap();
+        let rng = &mut StdRng::from_seed([0u8; 32]);
+        let evals = [
+            ProofEvaluations::dummy_with_witness_evaluations(array_init(|_| Fp::rand(rng))),
+            ProofEvaluations::dummy_with_witness_evaluations(array_init(|_| Fp::rand(rng))),
+        ];
+        let one = Fp::from(1u32);
+        let constants = Constants {
+            alpha: one,
+            beta: one,
+            gamma: one,
+            joint_combiner: None,
+            endo_coefficient: one,
+            mds: vec![vec![]],
+        };
+        let pt = Fp::rand(rng);
+        let eval = |e: &E<Fp>| e.evaluate_(domain.d1, pt, &evals, &constants).unwrap();
+        assert_eq!(eval(&folded_right), eval(&unfolded_right));
+        assert_eq!(eval(&folded_left), eval(&unfolded_left));
+    }
+
+    #[test]
+    fn test_degree_of_mul() {
+        let d1_size = 16;
+        let c: E<Fp> = constant(Fp::from(3u32));
+        let w0: E<Fp> = witness_curr(0);
+        let w1: E<Fp> = witness_next(1);
+
+        // constant * constant
+        assert_eq!((c.clone() * c.clone()).degree(d1_size), 0);
+
+        // constant * cell, on both sides
+        assert_eq!((c.clone() * w0.clone()).degree(d1_size), d1_size);
+        assert_eq!((w0.clone() * c.clone()).degree(d1_size), d1_size);
+
+        // cell * cell
+        assert_eq!((w0.clone() * w1.clone()).degree(d1_size), 2 * d1_size);
+
+        // nested
+        let nested = c.clone() * (w0.clone() * (c.clone() * w1.clone()));
+        assert_eq!(nested.degree(d1_size), 2 * d1_size);
+        let nested = (c.clone() * w0.clone() + w1.clone()) * (c.clone() * c);
+        assert_eq!(nested.degree(d1_size), d1_size);
+        assert_eq!((w0.clone() * w1).square().degree(d1_size), 4 * d1_size);
+        assert_eq!(w0.pow(3).degree(d1_size), 3 * d1_size);
+    }
+
+    #[test]
+    fn test_eval_result_debug() {
+        let domain = D::<Fp>::new(8).unwrap();
+        let evals = Evaluations::from_vec_and_domain(vec![Fp::zero(); 8], domain);
+
+        let sub_evals = EvalResult::SubEvals {
+            domain: Domain::D8,
+            shift: 1,
+            evals: &evals,
+        };
+        assert_eq!(
+            format!("{:?}", sub_evals),
+            "SubEvals { domain: D8, shift: 1, len: 8 }"
+        );
+
+        let evals = EvalResult::Evals {
+            domain: Domain::D1,
+            evals: evals.clone(),
+        };
+        assert_eq!(format!("{:?}", evals), "Evals { domain: D1, len: 8 }");
+    }
+
+    #[test]
+    fn test_l0_1() {
+        // the product is the size of the domain, including for the degenerate domain of size 1
+        for log_size in 0..7 {
+            let size = 1usize << log_size;
+            let domain = D::<Fp>::new(size).unwrap();
+            assert_eq!(l0_1(domain), Fp::from(size as u64));
+        }
+    }
+
     #[test]
     fn test_unnormalized_lagrange_basis() {
         let domain = EvaluationDomains::<Fp>::create(2usize.pow(10) + ZK_ROWS as usize)
@@ -7,10 +7,20 @@ use num_traits::cast::ToPrimitive;
 use o1_utils::hasher::CryptoDigest;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
-use std::io::{Result as IoResult, Write};
+use std::{
+    fmt,
+    io::{Result as IoResult, Write},
+    str::FromStr,
+};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+use thiserror::Error;
 
 /// A row accessible from a given row, corresponds to the fact that we open all polynomials
 /// at `zeta` **and** `omega * zeta`.
+/// Constraints spanning more rows would require opening the polynomials at more points
+/// (`omega^2 * zeta`, ...), and thus more evaluations in the proof
+/// (see [ProverProof::evals](crate::proof::ProverProof::evals)).
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 #[cfg_attr(
@@ -55,6 +65,7 @@ impl CurrOrNext {
     Hash,
     PartialOrd,
     Ord,
+    EnumIter,
 )]
 #[cfg_attr(
     feature = "ocaml_types",
@@ -94,6 +105,43 @@ pub enum GateType {
     RangeCheck1 = 17,
 }
 
+/// The name of the gate type, which is the name of its variant (for example `"Poseidon"`).
+impl fmt::Display for GateType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// The error returned when parsing a name that is not the name of a [GateType].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("unknown gate type {0:?}")]
+pub struct UnknownGateType(pub String);
+
+/// Parses a gate type from its name, as displayed by [GateType]'s [fmt::Display] implementation.
+impl FromStr for GateType {
+    type Err = UnknownGateType;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        GateType::iter()
+            .find(|gate| gate.to_string() == s)
+            .ok_or_else(|| UnknownGateType(s.to_string()))
+    }
+}
+
+/// Returns the number of consecutive rows, starting with its own,
+/// that the constraints of a gate of type `gate` refer to.
+/// For example, a [GateType::Poseidon] gate reads the output of its rounds
+/// from the next row, while a [GateType::Generic] gate only uses its own row.
+/// This is useful to size a circuit from the gates it uses.
+pub fn rows_per_gate(gate: GateType) -> usize {
+    use GateType::*;
+    match gate {
+        Zero | Generic | CompleteAdd | EndoMulScalar | Lookup | RangeCheck0 => 1,
+        Poseidon | VarBaseMul | EndoMul | ChaCha0 | ChaCha1 | ChaCha2 | ChaChaFinal
+        | CairoClaim | CairoInstruction | CairoFlags | CairoTransition | RangeCheck1 => 2,
+    }
+}
+
 #[serde_as]
 #[derive(Clone, Debug, Serialize, Deserialize)]
 /// A single gate in a circuit.
@@ -309,6 +357,90 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_gate_type_names() {
+        for gate in GateType::iter() {
+            assert_eq!(gate.to_string().parse(), Ok(gate));
+        }
+        assert_eq!("CompleteAdd".parse(), Ok(GateType::CompleteAdd));
+        assert_eq!(
+            "Double".parse::<GateType>(),
+            Err(UnknownGateType("Double".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_rows_per_gate() {
+        use crate::circuits::{
+            argument::ConstraintRegistry,
+            expr::PolishToken,
+            polynomials::{
+                chacha::{ChaCha0, ChaCha1, ChaCha2, ChaChaFinal},
+                complete_add::CompleteAdd,
+                endomul_scalar::EndomulScalar,
+                endosclmul::EndosclMul,
+                generic::Generic,
+                poseidon::Poseidon,
+                range_check::{RangeCheck0, RangeCheck1},
+                turshi::{Claim, Flags, Instruction, Transition},
+                varbasemul::VarbaseMul,
+            },
+        };
+
+        let mut registry = ConstraintRegistry::<Fp>::default();
+        registry.register_argument::<Generic<Fp>>();
+        registry.register_argument::<Poseidon<Fp>>();
+        registry.register_argument::<CompleteAdd<Fp>>();
+        registry.register_argument::<VarbaseMul<Fp>>();
+        registry.register_argument::<EndosclMul<Fp>>();
+        registry.register_argument::<EndomulScalar<Fp>>();
+        registry.register_argument::<ChaCha0<Fp>>();
+        registry.register_argument::<ChaCha1<Fp>>();
+        registry.register_argument::<ChaCha2<Fp>>();
+        registry.register_argument::<ChaChaFinal<Fp>>();
+        registry.register_argument::<Claim<Fp>>();
+        registry.register_argument::<Instruction<Fp>>();
+        registry.register_argument::<Flags<Fp>>();
+        registry.register_argument::<Transition<Fp>>();
+        registry.register_argument::<RangeCheck0<Fp>>();
+        registry.register_argument::<RangeCheck1<Fp>>();
+
+        for gate in [
+            GateType::Zero,
+            GateType::Generic,
+            GateType::Poseidon,
+            GateType::CompleteAdd,
+            GateType::VarBaseMul,
+            GateType::EndoMul,
+            GateType::EndoMulScalar,
+            GateType::ChaCha0,
+            GateType::ChaCha1,
+            GateType::ChaCha2,
+            GateType::ChaChaFinal,
+            GateType::Lookup,
+            GateType::CairoClaim,
+            GateType::CairoInstruction,
+            GateType::CairoFlags,
+            GateType::CairoTransition,
+            GateType::RangeCheck0,
+            GateType::RangeCheck1,
+        ] {
+            // gates without constraints only use their own row
+            let max_shift = registry.get(gate).map_or(0, |constraints| {
+                constraints
+                    .iter()
+                    .flat_map(|c| c.to_polish())
+                    .filter_map(|t| match t {
+                        PolishToken::Cell(v) => Some(v.row.shift()),
+                        _ => None,
+                    })
+                    .max()
+                    .unwrap_or(0)
+            });
+            assert_eq!(rows_per_gate(gate), max_shift + 1, "{:?}", gate);
+        }
+    }
+
     proptest! {
         #[test]
         fn test_gate_serialization(cg in arb_circuit_gate()) {
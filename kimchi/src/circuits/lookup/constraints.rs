@@ -29,16 +29,22 @@ pub const ZK_ROWS: usize = 3;
 
 /// Pad with zeroes and then add 3 random elements in the last two
 /// rows for zero knowledge.
+/// If `zero_knowledge` is `false`, the last rows are padded with zeroes as well.
 pub fn zk_patch<R: Rng + ?Sized, F: FftField>(
     mut e: Vec<F>,
     d: D<F>,
+    zero_knowledge: bool,
     rng: &mut R,
 ) -> Evaluations<F, D<F>> {
     let n = d.size();
     let k = e.len();
     assert!(k <= n - ZK_ROWS);
     e.extend((0..((n - ZK_ROWS) - k)).map(|_| F::zero()));
-    e.extend((0..ZK_ROWS).map(|_| F::rand(rng)));
+    if zero_knowledge {
+        e.extend((0..ZK_ROWS).map(|_| F::rand(rng)));
+    } else {
+        e.extend((0..ZK_ROWS).map(|_| F::zero()));
+    }
     Evaluations::<F, D<F>>::from_vec_and_domain(e, d)
 }
 
@@ -230,6 +236,7 @@ pub fn aggregation<R, F>(
     beta: F,
     gamma: F,
     sorted: &[Evaluations<F, D<F>>],
+    zero_knowledge: bool,
     rng: &mut R,
     lookup_info: &LookupInfo,
 ) -> Result<Evaluations<F, D<F>>, ProverError>
@@ -310,7 +317,7 @@ where
             lookup_aggreg[i + 1] *= prev;
         });
 
-    let res = zk_patch(lookup_aggreg, d1, rng);
+    let res = zk_patch(lookup_aggreg, d1, zero_knowledge, rng);
 
     // check that the final evaluation is equal to 1
     if cfg!(debug_assertions) {
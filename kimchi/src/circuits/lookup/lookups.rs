@@ -278,6 +278,7 @@ impl<F: Copy> JointLookup<SingleLookup<F>, LookupTableID> {
 #[derive(
     Copy, Clone, Serialize, Deserialize, Debug, EnumIter, PartialEq, Eq, PartialOrd, Ord, Hash,
 )]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
 pub enum LookupPattern {
     ChaCha,
     ChaChaFinal,
@@ -7,3 +7,69 @@ macro_rules! ensure_eq {
         }
     };
 }
+
+/// Builds a constraint (an [E](crate::circuits::expr::E)) from a near-mathematical expression.
+/// The usual `+`, `-` and `*` operators are supported, as well as:
+/// - `w[i]` for the witness column `i` on the current row, and `w_next[i]` on the next row,
+/// - `alpha`, `beta` and `gamma` for the challenges,
+/// - integer literals,
+/// - `x^n` for `x` raised to the literal power `n`,
+/// - other variables (which are cloned) and function calls, which must be expressions.
+///
+/// For example, `constraint!(w[0] * w[1] - w_next[2] + alpha^2 * (w[3] - 1))`.
+#[macro_export]
+macro_rules! constraint {
+    // `atom ^ n`
+    (@atom [$($out:tt)*] {$($atom:tt)*} ^ $n:literal $($rest:tt)*) => {
+        $crate::constraint!(@munch [$($out)* ($($atom)*).pow($n)] $($rest)*)
+    };
+    (@atom [$($out:tt)*] {$($atom:tt)*} $($rest:tt)*) => {
+        $crate::constraint!(@munch [$($out)* ($($atom)*)] $($rest)*)
+    };
+
+    (@munch [$($out:tt)*]) => { $($out)* };
+    (@munch [$($out:tt)*] w_next [$i:expr] $($rest:tt)*) => {
+        $crate::constraint!(@atom [$($out)*] {$crate::circuits::expr::witness_next($i)} $($rest)*)
+    };
+    (@munch [$($out:tt)*] w [$i:expr] $($rest:tt)*) => {
+        $crate::constraint!(@atom [$($out)*] {$crate::circuits::expr::witness_curr($i)} $($rest)*)
+    };
+    (@munch [$($out:tt)*] alpha $($rest:tt)*) => {
+        $crate::constraint!(@atom [$($out)*] {
+            $crate::circuits::expr::Expr::Constant($crate::circuits::expr::ConstantExpr::Alpha)
+        } $($rest)*)
+    };
+    (@munch [$($out:tt)*] beta $($rest:tt)*) => {
+        $crate::constraint!(@atom [$($out)*] {
+            $crate::circuits::expr::Expr::Constant($crate::circuits::expr::ConstantExpr::Beta)
+        } $($rest)*)
+    };
+    (@munch [$($out:tt)*] gamma $($rest:tt)*) => {
+        $crate::constraint!(@atom [$($out)*] {
+            $crate::circuits::expr::Expr::Constant($crate::circuits::expr::ConstantExpr::Gamma)
+        } $($rest)*)
+    };
+    (@munch [$($out:tt)*] ($($inner:tt)*) $($rest:tt)*) => {
+        $crate::constraint!(@atom [$($out)*] {$crate::constraint!($($inner)*)} $($rest)*)
+    };
+    (@munch [$($out:tt)*] $lit:literal $($rest:tt)*) => {
+        $crate::constraint!(@atom [$($out)*] {{
+            let n: u64 = $lit;
+            $crate::circuits::expr::E::from(n)
+        }} $($rest)*)
+    };
+    (@munch [$($out:tt)*] $f:ident ($($args:tt)*) $($rest:tt)*) => {
+        $crate::constraint!(@atom [$($out)*] {$f($($args)*)} $($rest)*)
+    };
+    (@munch [$($out:tt)*] $var:ident $($rest:tt)*) => {
+        $crate::constraint!(@atom [$($out)*] {$var.clone()} $($rest)*)
+    };
+    // operators
+    (@munch [$($out:tt)*] $op:tt $($rest:tt)*) => {
+        $crate::constraint!(@munch [$($out)* $op] $($rest)*)
+    };
+
+    ($($t:tt)*) => {
+        $crate::constraint!(@munch [] $($t)*)
+    };
+}
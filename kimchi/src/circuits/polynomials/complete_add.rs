@@ -136,7 +136,7 @@ where
                 s.clone().double() * y1.clone() - x1_squared.clone().double() - x1_squared;
             let add_case = x21 * s.clone() - y21.clone();
 
-            res.push(same_x.clone() * dbl_case + (E::one() - same_x.clone()) * add_case);
+            res.push(if_then_else(same_x.clone(), dbl_case, add_case));
         }
 
         // Unconditionally constrain
@@ -34,7 +34,9 @@
 //~
 
 use crate::circuits::{
+    argument::{Argument, ArgumentType},
     constraints::ConstraintSystem,
+    expr::prologue::*,
     gate::{CircuitGate, GateType},
     polynomial::COLUMNS,
     wires::GateWires,
@@ -45,6 +47,7 @@ use ark_poly::{
 };
 use array_init::array_init;
 use rayon::prelude::*;
+use std::marker::PhantomData;
 
 /// Number of constraints produced by the gate.
 pub const CONSTRAINTS: u32 = 2;
@@ -165,6 +168,36 @@ impl<F: FftField> CircuitGate<F> {
 //~
 //~ where the $c_i$ are the [coefficients]().
 
+/// The double generic gate, expressed with the expression framework.
+/// The coefficients of the gate are read from the
+/// [Column::Coefficient](crate::circuits::expr::Column::Coefficient) columns.
+///
+/// It is part of the linearization like the other gates,
+/// but the prover still special-cases the generic gate in the quotient polynomial
+/// (see [ConstraintSystem::gnrc_quot]).
+pub struct Generic<F>(PhantomData<F>);
+
+impl<F> Argument<F> for Generic<F>
+where
+    F: FftField,
+{
+    const ARGUMENT_TYPE: ArgumentType = ArgumentType::Gate(GateType::Generic);
+    const CONSTRAINTS: u32 = CONSTRAINTS;
+
+    fn constraints() -> Vec<E<F>> {
+        let generic_gate = |coeff_offset, register_offset| {
+            let c = |i| coeff(coeff_offset + i);
+            let w = |i| witness_curr(register_offset + i);
+            c(0) * w(0) + c(1) * w(1) + c(2) * w(2) + c(3) * w(0) * w(1) + c(4)
+        };
+
+        vec![
+            generic_gate(0, 0),
+            generic_gate(GENERIC_COEFFS, GENERIC_REGISTERS),
+        ]
+    }
+}
+
 impl<F: FftField + SquareRootField> ConstraintSystem<F> {
     /// generic constraint quotient poly contribution computation
     pub fn gnrc_quot(
@@ -239,6 +272,7 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
     /// alpha * generic(zeta) * w[1](zeta),
     /// alpha * generic(zeta) * w[2](zeta)
     /// ```
+    #[cfg(test)]
     pub fn gnrc_scalars(
         mut alphas: impl Iterator<Item = F>,
         w_zeta: &[F; COLUMNS],
@@ -275,7 +309,10 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
         res
     }
 
-    /// generic constraint linearization poly contribution computation
+    /// generic constraint linearization poly contribution computation.
+    /// The prover and the verifier get this contribution from the linearization of [Generic],
+    /// this is only kept to test that linearization.
+    #[cfg(test)]
     pub fn gnrc_lnrz(
         &self,
         alphas: impl Iterator<Item = F>,
@@ -305,6 +342,33 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
     }
 }
 
+/// Evaluates at `pt` the public input polynomial, which takes the value `public[i]`
+/// at the `i`-th element of `domain` and is zero on the rest of the domain.
+/// This uses the Lagrange basis directly, `L_i(x) = w^i (x^n - 1) / (n (x - w^i))`,
+/// instead of interpolating the polynomial.
+/// The prover and the verifier both use this, so that they agree on the encoding of the public input.
+pub fn eval_public_input<F: FftField>(public: &[F], domain: D<F>, pt: F) -> F {
+    let mut denominators: Vec<_> = domain
+        .elements()
+        .take(public.len())
+        .map(|w| pt - w)
+        .collect();
+
+    // the Lagrange formula does not apply on the domain
+    if let Some(i) = denominators.iter().position(|d| d.is_zero()) {
+        return public[i];
+    }
+    ark_ff::fields::batch_inversion::<F>(&mut denominators);
+
+    let sum = public
+        .iter()
+        .zip(denominators)
+        .zip(domain.elements())
+        .map(|((p, l), w)| l * p * w)
+        .fold(F::zero(), |x, y| x + y);
+    sum * domain.evaluate_vanishing_polynomial(pt) * domain.size_inv
+}
+
 // -------------------------------------------------
 
 pub mod testing {
@@ -492,8 +556,12 @@ pub mod testing {
 mod tests {
     use super::*;
     use crate::circuits::wires::COLUMNS;
+    use crate::{
+        alphas::Alphas, circuits::expr::Constants, linearization::linearization_columns,
+        proof::ProofEvaluations,
+    };
     use ark_ff::{UniformRand, Zero};
-    use ark_poly::{EvaluationDomain, Polynomial};
+    use ark_poly::{EvaluationDomain, Polynomial, UVPolynomial};
     use array_init::array_init;
     use mina_curves::pasta::fp::Fp;
     use rand::SeedableRng;
@@ -553,4 +621,168 @@ mod tests {
         let z_h_zeta = cs.domain.d1.evaluate_vanishing_polynomial(zeta);
         assert!(f_zeta == t_zeta * z_h_zeta);
     }
+
+    #[test]
+    fn test_eval_public_input() {
+        let rng = &mut rand::rngs::StdRng::from_seed([0; 32]);
+        let domain = D::<Fp>::new(16).unwrap();
+        let public: Vec<_> = (0..5).map(|_| Fp::rand(rng)).collect();
+
+        let mut evals = public.clone();
+        evals.resize(domain.size(), Fp::zero());
+        let poly = Evaluations::from_vec_and_domain(evals, domain).interpolate();
+
+        // outside and on the domain
+        let pt = Fp::rand(rng);
+        for x in std::iter::once(pt).chain(domain.elements()) {
+            assert_eq!(eval_public_input(&public, domain, x), poly.evaluate(&x));
+        }
+        assert!(eval_public_input(&[], domain, pt).is_zero());
+    }
+
+    #[test]
+    fn test_generic_argument() {
+        let gates = testing::create_circuit::<Fp>(0, 0);
+        let cs = ConstraintSystem::fp_for_testing(gates);
+
+        // random witness polynomials, that do not satisfy the constraints
+        let rng = &mut rand::rngs::StdRng::from_seed([0; 32]);
+        let n = cs.domain.d1.size();
+        let witness: [DensePolynomial<Fp>; COLUMNS] = array_init(|_| {
+            DensePolynomial::from_coefficients_vec((0..n).map(|_| Fp::rand(rng)).collect())
+        });
+        let domain_evals = cs.evaluate(&witness, &DensePolynomial::zero());
+
+        let alpha = Fp::rand(rng);
+        let mut alphas = Alphas::default();
+        alphas.register(ArgumentType::Gate(GateType::Generic), CONSTRAINTS);
+        let expr = Generic::<Fp>::combined_constraints(&alphas);
+        alphas.instantiate(alpha);
+
+        let constants = Constants {
+            alpha,
+            beta: Fp::zero(),
+            gamma: Fp::zero(),
+            joint_combiner: None,
+            endo_coefficient: Fp::zero(),
+            mds: vec![vec![]],
+        };
+        let env = cs.fixed_environment().with_witness(
+            &domain_evals.d8.this.w,
+            &domain_evals.d8.this.z,
+            constants,
+            None,
+        );
+
+        // the expression describes the same constraints as the special-cased generic gate
+        let expected = cs.gnrc_quot(
+            alphas.get_alphas(ArgumentType::Gate(GateType::Generic), CONSTRAINTS),
+            &domain_evals.d4.this.w,
+        );
+        assert_eq!(expr.evaluations(&env), expected);
+    }
+
+    #[test]
+    fn test_full_constraint_generic() {
+        let gates = testing::create_circuit::<Fp>(0, 0);
+        let cs = ConstraintSystem::fp_for_testing(gates);
+
+        // random witness polynomials, that do not satisfy the constraints
+        let rng = &mut rand::rngs::StdRng::from_seed([0; 32]);
+        let n = cs.domain.d1.size();
+        let witness: [DensePolynomial<Fp>; COLUMNS] = array_init(|_| {
+            DensePolynomial::from_coefficients_vec((0..n).map(|_| Fp::rand(rng)).collect())
+        });
+        let domain_evals = cs.evaluate(&witness, &DensePolynomial::zero());
+
+        let alpha = Fp::rand(rng);
+        let mut alphas = Alphas::default();
+        alphas.register(ArgumentType::Gate(GateType::Generic), CONSTRAINTS);
+        alphas.instantiate(alpha);
+
+        let constants = Constants {
+            alpha,
+            beta: Fp::zero(),
+            gamma: Fp::zero(),
+            joint_combiner: None,
+            endo_coefficient: Fp::zero(),
+            mds: cs.fr_sponge_params.mds.clone(),
+        };
+        let env = cs.fixed_environment().with_witness(
+            &domain_evals.d8.this.w,
+            &domain_evals.d8.this.z,
+            constants,
+            None,
+        );
+
+        // the circuit only has generic gates, so the full constraint is the generic constraint
+        let expected = cs.gnrc_quot(
+            alphas.get_alphas(ArgumentType::Gate(GateType::Generic), CONSTRAINTS),
+            &domain_evals.d4.this.w,
+        );
+        assert!(!expected.evals.iter().all(Fp::is_zero));
+        assert_eq!(
+            cs.full_constraint(0).evaluations(&env).interpolate(),
+            expected.interpolate()
+        );
+    }
+
+    #[test]
+    fn test_generic_linearization() {
+        let gates = testing::create_circuit::<Fp>(0, 0);
+        let cs = ConstraintSystem::fp_for_testing(gates);
+
+        let rng = &mut rand::rngs::StdRng::from_seed([0; 32]);
+        let n = cs.domain.d1.size();
+        let witness: [DensePolynomial<Fp>; COLUMNS] = array_init(|_| {
+            DensePolynomial::from_coefficients_vec((0..n).map(|_| Fp::rand(rng)).collect())
+        });
+        let domain_evals = cs.evaluate(&witness, &DensePolynomial::zero());
+
+        let alpha = Fp::rand(rng);
+        let mut alphas = Alphas::default();
+        alphas.register(ArgumentType::Gate(GateType::Generic), CONSTRAINTS);
+        let linearization = Generic::<Fp>::combined_constraints(&alphas)
+            .linearize(linearization_columns::<Fp>(None))
+            .unwrap();
+        alphas.instantiate(alpha);
+
+        let constants = Constants {
+            alpha,
+            beta: Fp::zero(),
+            gamma: Fp::zero(),
+            joint_combiner: None,
+            endo_coefficient: Fp::zero(),
+            mds: vec![vec![]],
+        };
+        let env = cs.fixed_environment().with_witness(
+            &domain_evals.d8.this.w,
+            &domain_evals.d8.this.z,
+            constants,
+            None,
+        );
+
+        // the evaluations of the witness and the generic selector at zeta and zeta * omega
+        let zeta = Fp::rand(rng);
+        let evals = [zeta, zeta * cs.domain.d1.group_gen].map(|pt| ProofEvaluations {
+            w: array_init(|col| witness[col].evaluate(&pt)),
+            z: Fp::zero(),
+            s: array_init(|_| Fp::zero()),
+            lookup: None,
+            generic_selector: cs.genericm.evaluate(&pt),
+            poseidon_selector: Fp::zero(),
+        });
+
+        // the linearization of the expression is the special-cased generic contribution
+        let (constant_term, f) = linearization.to_polynomial(&env, zeta, &evals);
+        assert!(constant_term.is_zero());
+        let expected = cs
+            .gnrc_lnrz(
+                alphas.get_alphas(ArgumentType::Gate(GateType::Generic), CONSTRAINTS),
+                &evals[0].w,
+                evals[0].generic_selector,
+            )
+            .interpolate();
+        assert_eq!(f, expected);
+    }
 }
@@ -42,7 +42,7 @@
 
 use crate::{
     circuits::{constraints::ConstraintSystem, polynomial::WitnessOverDomains, wires::*},
-    error::ProverError,
+    error::{PermutationError, ProverError},
     proof::ProofEvaluations,
 };
 use ark_ff::{FftField, SquareRootField, Zero};
@@ -55,10 +55,19 @@ use array_init::array_init;
 use blake2::{Blake2b512, Digest};
 use o1_utils::{ExtendedDensePolynomial, ExtendedEvaluations};
 use rand::{CryptoRng, RngCore};
+use std::collections::{HashMap, HashSet};
 
 /// Number of constraints produced by the argument.
 pub const CONSTRAINTS: u32 = 3;
 pub const ZK_ROWS: u64 = 3;
+
+/// The number of rows at the end of the domain that the prover fills with random values
+/// when zero-knowledge is enabled (see [zk_polynomial], which vanishes on them).
+/// The witness and the gates of a circuit must leave these rows free.
+pub const fn zero_knowledge_rows() -> usize {
+    ZK_ROWS as usize
+}
+
 /// Evaluates the polynomial
 /// (x - w^{n - 4}) (x - w^{n - 3}) * (x - w^{n - 2}) * (x - w^{n - 1})
 pub fn eval_vanishes_on_last_4_rows<F: FftField>(domain: D<F>, x: F) -> F {
@@ -186,7 +195,69 @@ where
     }
 }
 
+/// Checks that the permutation polynomials `sigmas` (one per permuted column) encode a permutation
+/// of the wire positions of `domain`, that is of the elements `shift_col * omega^row` (see [Shifts]).
+/// Each cell `(col, row)` is mapped to `sigmas[col](omega^row)`,
+/// which must be a wire position that no other cell is mapped to.
+/// This is meant to audit custom wiring, as sigmas that do not encode a permutation
+/// can lead to proofs that do not enforce the intended copy constraints.
+pub fn validate_permutation<F: FftField + SquareRootField>(
+    sigmas: &[DensePolynomial<F>],
+    domain: D<F>,
+) -> Result<(), PermutationError> {
+    if sigmas.len() != PERMUTS {
+        return Err(PermutationError::WrongNumberOfSigmas(sigmas.len()));
+    }
+
+    let shifts = Shifts::new(&domain);
+    let positions: HashMap<F, (usize, usize)> = shifts
+        .map
+        .iter()
+        .enumerate()
+        .flat_map(|(col, elems)| {
+            elems
+                .iter()
+                .enumerate()
+                .map(move |(row, elem)| (*elem, (col, row)))
+        })
+        .collect();
+
+    // there are as many cells as wire positions, so an injective mapping is a bijection
+    let mut images = HashSet::new();
+    for (col, sigma) in sigmas.iter().enumerate() {
+        let evals = sigma.evaluate_over_domain_by_ref(domain);
+        for (row, x) in evals.evals.iter().enumerate() {
+            let position = positions
+                .get(x)
+                .ok_or(PermutationError::NotAWirePosition { col, row })?;
+            if !images.insert(position) {
+                return Err(PermutationError::NotABijection { col, row });
+            }
+        }
+    }
+
+    Ok(())
+}
+
 impl<F: FftField + SquareRootField> ConstraintSystem<F> {
+    /// The permutation polynomials whose evaluations are included in a proof.
+    /// Only the first `PERMUTS - 1` of them are evaluated,
+    /// as the last one only appears in the linearization (see [ConstraintSystem::perm_lnrz]).
+    pub fn evaluated_sigmas(&self) -> &[DensePolynomial<F>] {
+        &self.sigmam[..PERMUTS - 1]
+    }
+
+    /// The chunked evaluations at `pt` of the permutation polynomials included in a proof,
+    /// see [ConstraintSystem::evaluated_sigmas].
+    pub fn sigma_evaluations(&self, pt: F, max_poly_size: usize) -> [Vec<F>; PERMUTS - 1] {
+        let sigmas = self.evaluated_sigmas();
+        array_init(|i| {
+            sigmas[i]
+                .to_chunked_polynomial(max_poly_size)
+                .evaluate_chunks(pt)
+        })
+    }
+
     /// permutation quotient poly contribution computation
     #[allow(clippy::type_complexity)]
     pub fn perm_quot(
@@ -426,6 +497,12 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
                 .fold(F::one(), |x, y| x * y)
         }
 
+        // a zero denominator would be silently left as zero by the batch inversion,
+        // which happens when a witness value is equal to `-(sigma * beta + gamma)`
+        if let Some(row) = z[1..=n - 3].iter().position(|d| d.is_zero()) {
+            return Err(ProverError::PermutationDenominatorZero { row });
+        }
+
         ark_ff::fields::batch_inversion::<F>(&mut z[1..=n - 3]);
 
         for j in 0..n - 3 {
@@ -445,10 +522,68 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
 
         //~ Finally, randomize the last `EVAL_POINTS` evaluations $z(g^{n-2})$ and $z(g^{n-1})$,
         //~ in order to add zero-knowledge to the protocol.
-        z[n - 2] = F::rand(rng);
-        z[n - 1] = F::rand(rng);
+        if self.zero_knowledge {
+            z[n - 2] = F::rand(rng);
+            z[n - 1] = F::rand(rng);
+        }
 
         let res = Evaluations::<F, D<F>>::from_vec_and_domain(z, self.domain.d1).interpolate();
         Ok(res)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuits::polynomials::generic::testing::create_circuit;
+    use ark_ff::UniformRand;
+    use mina_curves::pasta::fp::Fp;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_validate_permutation() {
+        let cs = ConstraintSystem::fp_for_testing(create_circuit::<Fp>(0, 0));
+        let domain = cs.domain.d1;
+        validate_permutation(&cs.sigmam, domain).unwrap();
+
+        assert!(matches!(
+            validate_permutation(&cs.sigmam[1..], domain),
+            Err(PermutationError::WrongNumberOfSigmas(6))
+        ));
+
+        // two cells mapped to the same position
+        let mut sigmas = cs.sigmam.clone();
+        sigmas[1] = sigmas[0].clone();
+        assert!(matches!(
+            validate_permutation(&sigmas, domain),
+            Err(PermutationError::NotABijection { col: 1, row: 0 })
+        ));
+
+        // a cell mapped outside of the wire positions
+        let mut sigmas = cs.sigmam.clone();
+        sigmas[2] = &sigmas[2] + &DensePolynomial::from_coefficients_slice(&[Fp::from(1u32)]);
+        assert!(matches!(
+            validate_permutation(&sigmas, domain),
+            Err(PermutationError::NotAWirePosition { col: 2, row: 0 })
+        ));
+    }
+
+    #[test]
+    fn test_perm_aggreg_denominator_zero() {
+        let cs = ConstraintSystem::fp_for_testing(create_circuit::<Fp>(0, 0));
+        let n = cs.domain.d1.size();
+        let rng = &mut StdRng::from_seed([0u8; 32]);
+        let beta = Fp::rand(rng);
+        let gamma = Fp::rand(rng);
+
+        let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); n]);
+        cs.perm_aggreg(&witness, &beta, &gamma, rng).unwrap();
+
+        // cancel the factor of the denominator for the first column at row 2
+        witness[0][2] = -(cs.sigmal1[0][2] * beta + gamma);
+        assert!(matches!(
+            cs.perm_aggreg(&witness, &beta, &gamma, rng),
+            Err(ProverError::PermutationDenominatorZero { row: 2 })
+        ));
+    }
+}
@@ -212,6 +212,7 @@ impl<F: FftField + SquareRootField> CircuitGate<F> {
                 domain: cs.domain,
                 index: index_evals,
                 lookup: lookup_env,
+                column_names: HashMap::new(),
             }
         };
 
@@ -340,7 +341,7 @@ fn set_up_lookup_env_data<F: FftField>(
     // Randomize the last `EVALS` rows in each of the sorted polynomials in order to add zero-knowledge to the protocol.
     let sorted: Vec<_> = sorted
         .into_iter()
-        .map(|chunk| lookup::constraints::zk_patch(chunk, cs.domain.d1, rng))
+        .map(|chunk| lookup::constraints::zk_patch(chunk, cs.domain.d1, cs.zero_knowledge, rng))
         .collect();
 
     let sorted_coeffs: Vec<_> = sorted.iter().map(|e| e.clone().interpolate()).collect();
@@ -361,6 +362,7 @@ fn set_up_lookup_env_data<F: FftField>(
         *beta,
         *gamma,
         &sorted,
+        cs.zero_knowledge,
         rng,
         lookup_info,
     )
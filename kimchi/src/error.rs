@@ -13,12 +13,18 @@ pub enum ProverError {
     #[error("the witness columns are not all the same size")]
     WitnessCsInconsistent,
 
+    #[error("the flat witness has {0} cells, which is not a multiple of the number of columns")]
+    FlatWitnessLength(usize),
+
     #[error("the proof could not be constructed: {0}")]
     Prover(&'static str),
 
     #[error("the permutation was not constructed correctly: {0}")]
     Permutation(&'static str),
 
+    #[error("the permutation argument has a zero denominator at row {row}")]
+    PermutationDenominatorZero { row: usize },
+
     #[error("the lookup failed to find a match in the table")]
     ValueNotInTable,
 
@@ -30,6 +36,11 @@ pub enum ProverError {
 
     #[error("wrong number of custom blinders given: {0}")]
     WrongBlinders(CommitmentError),
+
+    #[error(
+        "the quotient polynomial has degree {degree}, but should be of degree less than {max}"
+    )]
+    QuotientTooLarge { degree: usize, max: usize },
 }
 
 /// Errors that can arise when verifying a proof
@@ -58,6 +69,18 @@ pub enum VerifyError {
 
     #[error("runtime tables are used, but missing from the proof")]
     IncorrectRuntimeProof,
+
+    #[error("the commitments of the proof have more chunks than the domain allows")]
+    TooManyChunks,
+
+    #[error("the evaluations of {0} do not have as many chunks as its commitment")]
+    ChunkCountMismatch(&'static str),
+
+    #[error("the proof has {got} public inputs, but the circuit expects {expected}")]
+    PublicInputCountMismatch { got: usize, expected: usize },
+
+    #[error("expected {1} evaluations of {0}, got {2}")]
+    IncorrectEvaluationsLength(&'static str, usize, usize),
 }
 
 /// Errors that can arise when preparing the setup
@@ -70,6 +93,34 @@ pub enum SetupError {
     DomainCreation(&'static str),
 }
 
+/// Errors that can arise when checking that permutation polynomials encode a permutation,
+/// see [crate::circuits::polynomials::permutation::validate_permutation]
+#[derive(Error, Debug, Clone, Copy)]
+pub enum PermutationError {
+    #[error(
+        "expected {} permutation polynomials, got {0}",
+        crate::circuits::wires::PERMUTS
+    )]
+    WrongNumberOfSigmas(usize),
+
+    #[error("the permutation polynomial of column {col} maps row {row} to a value that is not a wire position")]
+    NotAWirePosition { col: usize, row: usize },
+
+    #[error("the permutation polynomial of column {col} maps row {row} to a wire position that is already the image of another cell")]
+    NotABijection { col: usize, row: usize },
+}
+
+/// Errors that can arise when instantiating precomputed powers of alpha,
+/// see [crate::alphas::Alphas::instantiate_with_powers]
+#[derive(Error, Debug, Clone, Copy)]
+pub enum AlphasError {
+    #[error("{registered} powers of alpha were registered, but only {given} were given")]
+    TooFewPowers { registered: usize, given: usize },
+
+    #[error("the given powers are not powers of alpha")]
+    NotPowersOfAlpha,
+}
+
 /// Errors that can arise when creating a verifier index
 #[derive(Error, Debug, Clone)]
 pub enum VerifierIndexError {
@@ -1,13 +1,14 @@
 //! This module implements the linearization.
 
 use crate::alphas::Alphas;
-use crate::circuits::argument::{Argument, ArgumentType};
+use crate::circuits::argument::{Argument, ArgumentType, ConstraintRegistry};
 use crate::circuits::lookup;
 use crate::circuits::lookup::constraints::LookupConfiguration;
 use crate::circuits::polynomials::chacha::{ChaCha0, ChaCha1, ChaCha2, ChaChaFinal};
 use crate::circuits::polynomials::complete_add::CompleteAdd;
 use crate::circuits::polynomials::endomul_scalar::EndomulScalar;
 use crate::circuits::polynomials::endosclmul::EndosclMul;
+use crate::circuits::polynomials::generic::Generic;
 use crate::circuits::polynomials::permutation;
 use crate::circuits::polynomials::poseidon::Poseidon;
 use crate::circuits::polynomials::range_check;
@@ -19,6 +20,37 @@ use crate::circuits::{
 };
 use ark_ff::{FftField, SquareRootField};
 
+/// Registers the constraints of all the gates,
+/// the chacha and range check gates being only registered if they are enabled.
+/// This is the single source of the gate constraints,
+/// used by [constraints_expr] and by the prover to compute the quotient polynomial.
+pub fn gate_constraints<F: FftField + SquareRootField>(
+    chacha: bool,
+    range_check: bool,
+) -> ConstraintRegistry<F> {
+    let mut registry = ConstraintRegistry::default();
+    registry.register_argument::<Generic<F>>();
+    registry.register_argument::<Poseidon<F>>();
+    registry.register_argument::<CompleteAdd<F>>();
+    registry.register_argument::<VarbaseMul<F>>();
+    registry.register_argument::<EndosclMul<F>>();
+    registry.register_argument::<EndomulScalar<F>>();
+
+    if chacha {
+        registry.register_argument::<ChaCha0<F>>();
+        registry.register_argument::<ChaCha1<F>>();
+        registry.register_argument::<ChaCha2<F>>();
+        registry.register_argument::<ChaChaFinal<F>>();
+    }
+
+    if range_check {
+        registry.register_argument::<range_check::RangeCheck0<F>>();
+        registry.register_argument::<range_check::RangeCheck1<F>>();
+    }
+
+    registry
+}
+
 pub fn constraints_expr<F: FftField + SquareRootField>(
     chacha: bool,
     range_check: bool,
@@ -34,22 +66,7 @@ pub fn constraints_expr<F: FftField + SquareRootField>(
         VarbaseMul::<F>::CONSTRAINTS,
     );
 
-    let mut expr = Poseidon::combined_constraints(&powers_of_alpha);
-    expr += VarbaseMul::combined_constraints(&powers_of_alpha);
-    expr += CompleteAdd::combined_constraints(&powers_of_alpha);
-    expr += EndosclMul::combined_constraints(&powers_of_alpha);
-    expr += EndomulScalar::combined_constraints(&powers_of_alpha);
-
-    if chacha {
-        expr += ChaCha0::combined_constraints(&powers_of_alpha);
-        expr += ChaCha1::combined_constraints(&powers_of_alpha);
-        expr += ChaCha2::combined_constraints(&powers_of_alpha);
-        expr += ChaChaFinal::combined_constraints(&powers_of_alpha);
-    }
-
-    if range_check {
-        expr += range_check::combined_constraints(&powers_of_alpha);
-    }
+    let mut expr = gate_constraints(chacha, range_check).all_combined_constraints(&powers_of_alpha);
 
     // permutation
     powers_of_alpha.register(ArgumentType::Permutation, permutation::CONSTRAINTS);
@@ -20,6 +20,22 @@ pub trait FrSponge<Fr: Field> {
     /// Absorbs the given evaluations into the sponge.
     // TODO: IMO this function should be inlined in prover/verifier
     fn absorb_evaluations(&mut self, p: &[Fr], e: &ProofEvaluations<Vec<Fr>>);
+
+    /// Absorbs all the scalars of a proof, as done by both the prover and the verifier:
+    /// the evaluations of the public polynomial and of the other polynomials
+    /// at each evaluation point, and then the evaluation of `ft` at `zeta * omega`.
+    fn absorb_proof_scalars(
+        &mut self,
+        p_eval: &[Vec<Fr>],
+        evals: &[ProofEvaluations<Vec<Fr>>],
+        ft_eval1: &Fr,
+    ) {
+        assert_eq!(p_eval.len(), evals.len());
+        for (p, e) in p_eval.iter().zip(evals) {
+            self.absorb_evaluations(p, e);
+        }
+        self.absorb(ft_eval1);
+    }
 }
 
 impl<Fr: PrimeField> FrSponge<Fr> for DefaultFrSponge<Fr, SC> {
@@ -44,47 +60,8 @@ impl<Fr: PrimeField> FrSponge<Fr> for DefaultFrSponge<Fr, SC> {
         self.last_squeezed = vec![];
         self.sponge.absorb(p);
 
-        let points = [
-            &e.z,
-            &e.generic_selector,
-            &e.poseidon_selector,
-            &e.w[0],
-            &e.w[1],
-            &e.w[2],
-            &e.w[3],
-            &e.w[4],
-            &e.w[5],
-            &e.w[6],
-            &e.w[7],
-            &e.w[8],
-            &e.w[9],
-            &e.w[10],
-            &e.w[11],
-            &e.w[12],
-            &e.w[13],
-            &e.w[14],
-            &e.s[0],
-            &e.s[1],
-            &e.s[2],
-            &e.s[3],
-            &e.s[4],
-            &e.s[5],
-        ];
-
-        for p in &points {
+        for p in e.absorption_order() {
             self.sponge.absorb(p);
         }
-
-        if let Some(lookup) = &e.lookup {
-            for s in &lookup.sorted {
-                self.sponge.absorb(s);
-            }
-            self.sponge.absorb(&lookup.aggreg);
-            self.sponge.absorb(&lookup.table);
-
-            if let Some(runtime_table) = &lookup.runtime {
-                self.sponge.absorb(runtime_table);
-            }
-        }
     }
 }
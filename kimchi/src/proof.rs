@@ -1,12 +1,17 @@
 //! This module implements the data structures of a proof.
 
-use crate::circuits::wires::{COLUMNS, PERMUTS};
+use crate::circuits::{
+    expr::Column,
+    gate::GateType,
+    wires::{COLUMNS, PERMUTS},
+};
+use crate::error::VerifyError;
 use ark_ec::AffineCurve;
-use ark_ff::{FftField, Zero};
+use ark_ff::{FftField, Field, Zero};
 use ark_poly::univariate::DensePolynomial;
 use array_init::array_init;
 use commitment_dlog::{commitment::PolyComm, evaluation_proof::OpeningProof};
-use o1_utils::ExtendedDensePolynomial;
+use o1_utils::{ExtendedDensePolynomial, FieldHelpers};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
@@ -113,7 +118,9 @@ pub struct ProverProof<G: AffineCurve> {
     #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub ft_eval1: G::ScalarField,
 
-    /// The public input
+    /// The public input.
+    /// Note that the values are not absorbed by the Fq-sponge,
+    /// only the (non-hiding) commitment to the public input polynomial is.
     #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
     pub public: Vec<G::ScalarField>,
 
@@ -144,6 +151,36 @@ impl<G: AffineCurve> RecursionChallenge<G> {
     }
 }
 
+impl<G: AffineCurve> ProverCommitments<G> {
+    /// Returns all the commitments, in the order in which they are absorbed by the Fq-sponge:
+    /// the witness columns, the lookup commitments if any (runtime table, sorted polynomials,
+    /// aggregation polynomial), the permutation polynomial `z`, and the quotient polynomial `t`.
+    pub fn all(&self) -> Vec<&PolyComm<G>> {
+        let mut res: Vec<_> = self.w_comm.iter().collect();
+        if let Some(lookup) = &self.lookup {
+            res.extend(lookup.runtime.iter());
+            res.extend(lookup.sorted.iter());
+            res.push(&lookup.aggreg);
+        }
+        res.push(&self.z_comm);
+        res.push(&self.t_comm);
+        res
+    }
+}
+
+impl<G: AffineCurve> ProverProof<G> {
+    /// The commitments of the proof,
+    /// for verifiers that need them before doing any of the per-proof work.
+    pub fn commitments(&self) -> &ProverCommitments<G> {
+        &self.commitments
+    }
+
+    /// All the commitments of the proof, see [ProverCommitments::all] for the order.
+    pub fn all_commitments(&self) -> Vec<&PolyComm<G>> {
+        self.commitments.all()
+    }
+}
+
 impl<F: Zero> ProofEvaluations<F> {
     pub fn dummy_with_witness_evaluations(w: [F; COLUMNS]) -> ProofEvaluations<F> {
         ProofEvaluations {
@@ -157,7 +194,212 @@ impl<F: Zero> ProofEvaluations<F> {
     }
 }
 
+impl<F> ProofEvaluations<F> {
+    /// Returns the evaluation of the polynomial of the column `col`,
+    /// or `None` if it is not part of these evaluations.
+    pub fn get(&self, col: Column) -> Option<&F> {
+        use Column::*;
+        match col {
+            Witness(i) => self.w.get(i),
+            Z => Some(&self.z),
+            LookupSorted(i) => self.lookup.as_ref()?.sorted.get(i),
+            LookupAggreg => self.lookup.as_ref().map(|l| &l.aggreg),
+            LookupTable => self.lookup.as_ref().map(|l| &l.table),
+            LookupRuntimeTable => self.lookup.as_ref()?.runtime.as_ref(),
+            Index(GateType::Poseidon) => Some(&self.poseidon_selector),
+            Index(GateType::Generic) => Some(&self.generic_selector),
+            Coefficient(_) | LookupKindIndex(_) | LookupRuntimeSelector | Index(_) => None,
+        }
+    }
+}
+
+/// Indexing by a column panics if the evaluations don't have it,
+/// use [ProofEvaluations::get] to handle that case.
+impl<F> std::ops::Index<Column> for ProofEvaluations<F> {
+    type Output = F;
+
+    fn index(&self, col: Column) -> &F {
+        self.get(col)
+            .unwrap_or_else(|| panic!("the evaluations have no evaluation of column {:?}", col))
+    }
+}
+
+impl<F: Clone> ProofEvaluations<F> {
+    /// Builds evaluations without lookups from slices of unchecked length,
+    /// typically coming from external data.
+    /// Returns an error instead of panicking if `w` is not of length `COLUMNS`,
+    /// or `s` of length `PERMUTS - 1`.
+    pub fn try_from_slices(
+        w: &[F],
+        s: &[F],
+        z: F,
+        generic_selector: F,
+        poseidon_selector: F,
+    ) -> Result<ProofEvaluations<F>, VerifyError> {
+        if w.len() != COLUMNS {
+            return Err(VerifyError::IncorrectEvaluationsLength(
+                "w",
+                COLUMNS,
+                w.len(),
+            ));
+        }
+        if s.len() != PERMUTS - 1 {
+            return Err(VerifyError::IncorrectEvaluationsLength(
+                "s",
+                PERMUTS - 1,
+                s.len(),
+            ));
+        }
+        Ok(ProofEvaluations {
+            w: array_init(|i| w[i].clone()),
+            z,
+            s: array_init(|i| s[i].clone()),
+            lookup: None,
+            generic_selector,
+            poseidon_selector,
+        })
+    }
+}
+
+impl<F: Clone + PartialEq> ProofEvaluations<F> {
+    /// Compares two sets of evaluations, typically the expected ones and the ones of a proof,
+    /// and returns the name of every evaluation that differs, followed by its value in `self` and in `other`.
+    /// The lookup evaluations are only compared if both sets have them,
+    /// and only the sorted evaluations present in both sets are compared.
+    pub fn diff(&self, other: &Self) -> Vec<(String, F, F)> {
+        let mut res = vec![];
+        let mut cmp = |name: String, x: &F, y: &F| {
+            if x != y {
+                res.push((name, x.clone(), y.clone()));
+            }
+        };
+
+        for (i, (x, y)) in self.w.iter().zip(other.w.iter()).enumerate() {
+            cmp(format!("w[{}]", i), x, y);
+        }
+        cmp("z".to_string(), &self.z, &other.z);
+        for (i, (x, y)) in self.s.iter().zip(other.s.iter()).enumerate() {
+            cmp(format!("s[{}]", i), x, y);
+        }
+        cmp(
+            "generic_selector".to_string(),
+            &self.generic_selector,
+            &other.generic_selector,
+        );
+        cmp(
+            "poseidon_selector".to_string(),
+            &self.poseidon_selector,
+            &other.poseidon_selector,
+        );
+
+        if let (Some(l1), Some(l2)) = (&self.lookup, &other.lookup) {
+            for (i, (x, y)) in l1.sorted.iter().zip(l2.sorted.iter()).enumerate() {
+                cmp(format!("lookup.sorted[{}]", i), x, y);
+            }
+            cmp("lookup.aggreg".to_string(), &l1.aggreg, &l2.aggreg);
+            cmp("lookup.table".to_string(), &l1.table, &l2.table);
+            if let (Some(x), Some(y)) = (&l1.runtime, &l2.runtime) {
+                cmp("lookup.runtime".to_string(), x, y);
+            }
+        }
+
+        res
+    }
+}
+
 impl<F: FftField> ProofEvaluations<Vec<F>> {
+    /// Checks that the evaluations of each polynomial are split in exactly as many chunks
+    /// as given by `chunks`, the number of chunks of the commitment to that polynomial.
+    /// With fewer chunks the evaluations would not open the commitment,
+    /// and with more they would not be bound by it.
+    pub fn validate_chunk_lengths(
+        &self,
+        chunks: &ProofEvaluations<usize>,
+    ) -> Result<(), VerifyError> {
+        let check = |name: &'static str, evals: &[F], expected: usize| {
+            if evals.len() == expected {
+                Ok(())
+            } else {
+                Err(VerifyError::ChunkCountMismatch(name))
+            }
+        };
+
+        check("z", &self.z, chunks.z)?;
+        check(
+            "generic selector",
+            &self.generic_selector,
+            chunks.generic_selector,
+        )?;
+        check(
+            "poseidon selector",
+            &self.poseidon_selector,
+            chunks.poseidon_selector,
+        )?;
+        for (w, &expected) in self.w.iter().zip(chunks.w.iter()) {
+            check("witness", w, expected)?;
+        }
+        for (s, &expected) in self.s.iter().zip(chunks.s.iter()) {
+            check("sigma", s, expected)?;
+        }
+
+        match (&self.lookup, &chunks.lookup) {
+            (None, None) => (),
+            (Some(l), Some(expected)) => {
+                if l.sorted.len() != expected.sorted.len() {
+                    return Err(VerifyError::ChunkCountMismatch("lookup sorted"));
+                }
+                for (sorted, &expected) in l.sorted.iter().zip(expected.sorted.iter()) {
+                    check("lookup sorted", sorted, expected)?;
+                }
+                check("lookup aggregation", &l.aggreg, expected.aggreg)?;
+                check("lookup table", &l.table, expected.table)?;
+                match (&l.runtime, expected.runtime) {
+                    (None, None) => (),
+                    (Some(runtime), Some(expected)) => check("runtime table", runtime, expected)?,
+                    _ => return Err(VerifyError::ChunkCountMismatch("runtime table")),
+                }
+            }
+            _ => return Err(VerifyError::ChunkCountMismatch("lookup")),
+        }
+
+        Ok(())
+    }
+
+    /// Returns the evaluations in the order in which they are absorbed by
+    /// [FrSponge::absorb_evaluations](crate::plonk_sponge::FrSponge::absorb_evaluations).
+    pub fn absorption_order(&self) -> Vec<&Vec<F>> {
+        let mut points = vec![&self.z, &self.generic_selector, &self.poseidon_selector];
+        points.extend(self.w.iter());
+        points.extend(self.s.iter());
+
+        if let Some(lookup) = &self.lookup {
+            points.extend(lookup.sorted.iter());
+            points.push(&lookup.aggreg);
+            points.push(&lookup.table);
+            points.extend(lookup.runtime.as_ref());
+        }
+
+        points
+    }
+
+    /// Serializes the evaluations to bytes, in the same order as they are absorbed
+    /// by the sponge, so that external commitments to the evaluations
+    /// stay consistent with the transcript.
+    /// Each field element is serialized with [FieldHelpers::to_bytes].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.absorption_order()
+            .into_iter()
+            .flatten()
+            .flat_map(|x| x.to_bytes())
+            .collect()
+    }
+
+    /// Combines the chunks of the evaluations at `pt` into the evaluations of the whole polynomials,
+    /// which is [ProofEvaluations::combine] with `pt^max_poly_size`.
+    pub fn combine_at(&self, pt: F, max_poly_size: usize) -> ProofEvaluations<F> {
+        self.combine(pt.pow(&[max_poly_size as u64]))
+    }
+
     pub fn combine(&self, pt: F) -> ProofEvaluations<F> {
         ProofEvaluations::<F> {
             s: array_init(|i| DensePolynomial::eval_polynomial(&self.s[i], pt)),
@@ -182,6 +424,161 @@ impl<F: FftField> ProofEvaluations<Vec<F>> {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::{One, UniformRand};
+    use ark_poly::{EvaluationDomain, Polynomial, Radix2EvaluationDomain as D, UVPolynomial};
+    use mina_curves::pasta::fp::Fp;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_to_bytes_follows_absorption_order() {
+        // give every evaluation a distinct value
+        let mut next = 0u64;
+        let mut fresh = || {
+            next += 1;
+            vec![Fp::from(next)]
+        };
+        let evals = ProofEvaluations {
+            z: fresh(),
+            generic_selector: fresh(),
+            poseidon_selector: fresh(),
+            w: array_init(|_| fresh()),
+            s: array_init(|_| fresh()),
+            lookup: None,
+        };
+
+        let size = Fp::size_in_bytes();
+        let bytes = evals.to_bytes();
+        assert_eq!(bytes.len(), (3 + COLUMNS + PERMUTS - 1) * size);
+
+        // z, the selectors, the witness and the permutation evaluations are absorbed in that order
+        for (i, chunk) in bytes.chunks(size).enumerate() {
+            assert_eq!(Fp::from_bytes(chunk).unwrap(), Fp::from(i as u64 + 1));
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let zero = Fp::zero();
+        let one = Fp::one();
+        let expected = ProofEvaluations::dummy_with_witness_evaluations([zero; COLUMNS]);
+        assert!(expected.diff(&expected).is_empty());
+
+        let mut got = expected.clone();
+        got.w[3] = one;
+        got.s[1] = one;
+        assert_eq!(
+            expected.diff(&got),
+            vec![
+                ("w[3]".to_string(), zero, one),
+                ("s[1]".to_string(), zero, one)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get() {
+        let zero = Fp::zero();
+        let one = Fp::one();
+        let mut w = [zero; COLUMNS];
+        w[2] = one;
+        let mut evals = ProofEvaluations::dummy_with_witness_evaluations(w);
+        evals.poseidon_selector = one;
+
+        assert_eq!(evals.get(Column::Witness(2)), Some(&one));
+        assert_eq!(evals.get(Column::Witness(COLUMNS)), None);
+        assert_eq!(evals.get(Column::Index(GateType::Poseidon)), Some(&one));
+        assert_eq!(evals.get(Column::Index(GateType::CompleteAdd)), None);
+        assert_eq!(evals.get(Column::Coefficient(0)), None);
+        assert_eq!(evals.get(Column::LookupAggreg), None);
+
+        evals.lookup = Some(LookupEvaluations {
+            sorted: vec![one],
+            aggreg: zero,
+            table: zero,
+            runtime: None,
+        });
+        assert_eq!(evals.get(Column::LookupSorted(0)), Some(&one));
+        assert_eq!(evals.get(Column::LookupSorted(1)), None);
+        assert_eq!(evals.get(Column::LookupAggreg), Some(&zero));
+        assert_eq!(evals.get(Column::LookupRuntimeTable), None);
+    }
+
+    #[test]
+    fn test_index_by_column() {
+        let mut w = [Fp::zero(); COLUMNS];
+        w[3] = Fp::one();
+        let evals = ProofEvaluations::dummy_with_witness_evaluations(w);
+        assert_eq!(evals[Column::Witness(3)], Fp::one());
+        assert_eq!(evals[Column::Z], Fp::zero());
+    }
+
+    #[test]
+    #[should_panic(expected = "no evaluation of column LookupAggreg")]
+    fn test_index_by_missing_column() {
+        let evals = ProofEvaluations::dummy_with_witness_evaluations([Fp::zero(); COLUMNS]);
+        let _ = evals[Column::LookupAggreg];
+    }
+
+    #[test]
+    fn test_combine_at_several_chunks() {
+        let rng = &mut StdRng::from_seed([0; 32]);
+        let max_poly_size = 4;
+        let omega = D::<Fp>::new(8).unwrap().group_gen;
+        let p = DensePolynomial::<Fp>::rand(2 * max_poly_size - 1, rng);
+        let chunked = p.to_chunked_polynomial(max_poly_size);
+
+        let zeta = Fp::rand(rng);
+        for pt in [zeta, zeta * omega] {
+            let chunks = chunked.evaluate_chunks(pt);
+            assert_eq!(chunks.len(), 2);
+            let evals = ProofEvaluations {
+                z: chunks.clone(),
+                generic_selector: chunks.clone(),
+                poseidon_selector: chunks.clone(),
+                w: array_init(|_| chunks.clone()),
+                s: array_init(|_| chunks.clone()),
+                lookup: None,
+            };
+            let combined = evals.combine_at(pt, max_poly_size);
+            assert_eq!(combined.z, p.evaluate(&pt));
+            assert_eq!(combined.w[0], p.evaluate(&pt));
+        }
+
+        // the chunks at zeta * omega do not combine with a power of zeta
+        let chunks = chunked.evaluate_chunks(zeta * omega);
+        let zeta_to_srs_len = zeta.pow(&[max_poly_size as u64]);
+        assert_ne!(
+            DensePolynomial::eval_polynomial(&chunks, zeta_to_srs_len),
+            p.evaluate(&(zeta * omega))
+        );
+    }
+
+    #[test]
+    fn test_try_from_slices() {
+        let zero = Fp::zero();
+        let one = Fp::one();
+        let w = [one; COLUMNS];
+        let s = [zero; PERMUTS - 1];
+
+        let evals = ProofEvaluations::try_from_slices(&w, &s, one, zero, zero).unwrap();
+        assert_eq!(evals.w, w);
+        assert_eq!(evals.s, s);
+        assert_eq!(evals.z, one);
+
+        assert!(matches!(
+            ProofEvaluations::try_from_slices(&w[1..], &s, one, zero, zero),
+            Err(VerifyError::IncorrectEvaluationsLength("w", COLUMNS, n)) if n == COLUMNS - 1
+        ));
+        assert!(matches!(
+            ProofEvaluations::try_from_slices(&w, &w, one, zero, zero),
+            Err(VerifyError::IncorrectEvaluationsLength("s", _, COLUMNS))
+        ));
+    }
+}
+
 //
 // OCaml types
 //
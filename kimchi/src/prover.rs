@@ -2,23 +2,13 @@
 
 use crate::{
     circuits::{
-        argument::{Argument, ArgumentType},
-        expr::{l0_1, Constants, Environment, LookupEnvironment},
+        argument::ArgumentType,
+        expr::{Constants, FixedEnvironment, LookupEnvironment},
         gate::GateType,
         lookup::{
             self, lookups::LookupsUsed, runtime_tables::RuntimeTable, tables::combine_table_entry,
         },
-        polynomials::{
-            chacha::{ChaCha0, ChaCha1, ChaCha2, ChaChaFinal},
-            complete_add::CompleteAdd,
-            endomul_scalar::EndomulScalar,
-            endosclmul::EndosclMul,
-            generic, permutation,
-            permutation::ZK_ROWS,
-            poseidon::Poseidon,
-            range_check,
-            varbasemul::VarbaseMul,
-        },
+        polynomials::{generic, permutation, permutation::ZK_ROWS},
         wires::{COLUMNS, PERMUTS},
     },
     error::ProverError,
@@ -28,6 +18,7 @@ use crate::{
         RecursionChallenge,
     },
     prover_index::ProverIndex,
+    verifier_index::quotient_commitment_chunks,
 };
 use ark_ec::ProjectiveCurve;
 use ark_ff::{FftField, Field, One, PrimeField, UniformRand, Zero};
@@ -36,13 +27,15 @@ use ark_poly::{
     Radix2EvaluationDomain as D, UVPolynomial,
 };
 use array_init::array_init;
-use commitment_dlog::commitment::{
-    b_poly_coefficients, BlindedCommitment, CommitmentCurve, PolyComm,
+use commitment_dlog::{
+    commitment::{b_poly_coefficients, BlindedCommitment, CommitmentCurve, PolyComm},
+    srs::SRS,
 };
 use itertools::Itertools;
-use o1_utils::ExtendedDensePolynomial as _;
+use o1_utils::{evaluations::interpolate_batch, ExtendedDensePolynomial as _};
 use oracle::{sponge::ScalarChallenge, FqSponge};
-use std::collections::HashMap;
+use rand::{CryptoRng, RngCore};
+use rayon::prelude::*;
 
 /// The result of a proof creation or verification.
 type Result<T> = std::result::Result<T, ProverError>;
@@ -56,8 +49,7 @@ macro_rules! check_constraint {
         if cfg!(debug_assertions) {
             let (_, res) = $evaluation
                 .interpolate_by_ref()
-                .divide_by_vanishing_poly($index.cs.domain.d1)
-                .unwrap();
+                .divide_by_x_n_minus_1($index.cs.domain.d1.size());
             if !res.is_zero() {
                 panic!("couldn't divide by vanishing polynomial: {}", $label);
             }
@@ -65,6 +57,17 @@ macro_rules! check_constraint {
     }};
 }
 
+/// The order in which the cells of a witness are laid out in a flat slice,
+/// see [ProverProof::create_from_flat].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WitnessLayout {
+    /// The cells of a row are contiguous: cell `(row, col)` is at index `row * COLUMNS + col`.
+    RowMajor,
+    /// The cells of a column are contiguous: cell `(row, col)` is at index `col * n + row`,
+    /// where `n` is the number of rows.
+    ColumnMajor,
+}
+
 /// Contains variables needed for lookup in the prover algorithm.
 #[derive(Default)]
 struct LookupContext<G, F>
@@ -108,11 +111,34 @@ where
     runtime_second_col_d8: Option<Evaluations<F, D<F>>>,
 }
 
+/// Pads the commitment of the quotient polynomial to `chunks` chunks,
+/// so that the number of points absorbed by the Fq-Sponge only depends on the circuit.
+/// The commitment has fewer chunks only when the higher degree coefficients of $t$ are 0,
+/// so the padding consists of hiding commitments to the 0 polynomial.
+/// This function will panic if the commitment already has more than `chunks` chunks.
+pub(crate) fn pad_quotient_commitment<G: CommitmentCurve>(
+    srs: &SRS<G>,
+    t_comm: &mut BlindedCommitment<G>,
+    chunks: usize,
+    rng: &mut (impl RngCore + CryptoRng),
+) {
+    // the degree of t is less than `max_quot_size`, so this never underflows
+    let dummies = chunks - t_comm.commitment.unshifted.len();
+    for _ in 0..dummies {
+        let w = <G::ScalarField as UniformRand>::rand(rng);
+        t_comm.commitment.unshifted.push(srs.h.mul(w).into_affine());
+        t_comm.blinders.unshifted.push(w);
+    }
+}
+
 impl<G: CommitmentCurve> ProverProof<G>
 where
     G::BaseField: PrimeField,
 {
-    /// This function constructs prover's zk-proof from the witness & the ProverIndex against SRS instance
+    /// This function constructs prover's zk-proof from the witness & the ProverIndex against SRS instance.
+    /// This is the entry point for standalone (non-recursive) proofs:
+    /// it is [ProverProof::create_recursive] without previous challenges or blinders,
+    /// so no `b_poly` polynomial is added to the opening.
     pub fn create<
         EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
         EFrSponge: FrSponge<G::ScalarField>,
@@ -129,30 +155,128 @@ where
             index,
             Vec::new(),
             None,
+            &[],
         )
     }
 
-    /// This function constructs prover's recursive zk-proof from the witness & the ProverIndex against SRS instance
+    /// Same as [ProverProof::create], but takes the witness as a single flat slice of `COLUMNS * n` cells,
+    /// laid out according to `layout`.
+    /// The columns are read directly from the slice, without building an intermediate transposed copy.
+    pub fn create_from_flat<
+        EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+        EFrSponge: FrSponge<G::ScalarField>,
+    >(
+        groupmap: &G::Map,
+        flat: &[G::ScalarField],
+        layout: WitnessLayout,
+        runtime_tables: &[RuntimeTable<G::ScalarField>],
+        index: &ProverIndex<G>,
+    ) -> Result<Self> {
+        if flat.len() % COLUMNS != 0 {
+            return Err(ProverError::FlatWitnessLength(flat.len()));
+        }
+        let n = flat.len() / COLUMNS;
+
+        let witness: [Vec<G::ScalarField>; COLUMNS] = match layout {
+            WitnessLayout::RowMajor => {
+                array_init(|col| flat.iter().skip(col).step_by(COLUMNS).copied().collect())
+            }
+            WitnessLayout::ColumnMajor => array_init(|col| flat[col * n..(col + 1) * n].to_vec()),
+        };
+
+        Self::create::<EFqSponge, EFrSponge>(groupmap, witness, runtime_tables, index)
+    }
+
+    /// This function constructs prover's recursive zk-proof from the witness & the ProverIndex against SRS instance.
+    /// The `transcript_prefix` is absorbed in the Fq-Sponge before any commitment,
+    /// and the verifier must be given the same prefix (see [crate::verifier::verify_with_prefix]).
     pub fn create_recursive<
         EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
         EFrSponge: FrSponge<G::ScalarField>,
+    >(
+        group_map: &G::Map,
+        witness: [Vec<G::ScalarField>; COLUMNS],
+        runtime_tables: &[RuntimeTable<G::ScalarField>],
+        index: &ProverIndex<G>,
+        prev_challenges: Vec<RecursionChallenge<G>>,
+        blinders: Option<[Option<PolyComm<G::ScalarField>>; COLUMNS]>,
+        transcript_prefix: &[G::BaseField],
+    ) -> Result<Self> {
+        Self::create_recursive_with_rng::<EFqSponge, EFrSponge, _>(
+            group_map,
+            witness,
+            runtime_tables,
+            index,
+            prev_challenges,
+            blinders,
+            transcript_prefix,
+            &mut rand::rngs::OsRng,
+        )
+    }
+
+    /// Same as [ProverProof::create_recursive], but the randomness of the proof
+    /// (blinders, zero-knowledge rows) is drawn from `rng`.
+    /// With a seeded `rng` the proof is deterministic, which is only useful for testing.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_recursive_with_rng<
+        EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+        EFrSponge: FrSponge<G::ScalarField>,
+        RNG: RngCore + CryptoRng,
+    >(
+        group_map: &G::Map,
+        witness: [Vec<G::ScalarField>; COLUMNS],
+        runtime_tables: &[RuntimeTable<G::ScalarField>],
+        index: &ProverIndex<G>,
+        prev_challenges: Vec<RecursionChallenge<G>>,
+        blinders: Option<[Option<PolyComm<G::ScalarField>>; COLUMNS]>,
+        transcript_prefix: &[G::BaseField],
+        rng: &mut RNG,
+    ) -> Result<Self> {
+        Self::create_recursive_with_environment::<EFqSponge, EFrSponge, _>(
+            group_map,
+            witness,
+            runtime_tables,
+            index,
+            &index.cs.fixed_environment(),
+            prev_challenges,
+            blinders,
+            transcript_prefix,
+            rng,
+        )
+    }
+
+    /// Same as [ProverProof::create_recursive_with_rng], but the part of the expression environment
+    /// that only depends on the circuit is given by `fixed_env`,
+    /// which must have been obtained from `index.cs.fixed_environment()`.
+    /// This lets callers proving many witnesses against the same circuit
+    /// build the [FixedEnvironment] once and reuse it for every proof.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_recursive_with_environment<
+        EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+        EFrSponge: FrSponge<G::ScalarField>,
+        RNG: RngCore + CryptoRng,
     >(
         group_map: &G::Map,
         mut witness: [Vec<G::ScalarField>; COLUMNS],
         runtime_tables: &[RuntimeTable<G::ScalarField>],
         index: &ProverIndex<G>,
+        fixed_env: &FixedEnvironment<G::ScalarField>,
         prev_challenges: Vec<RecursionChallenge<G>>,
         blinders: Option<[Option<PolyComm<G::ScalarField>>; COLUMNS]>,
+        transcript_prefix: &[G::BaseField],
+        rng: &mut RNG,
     ) -> Result<Self> {
+        debug_assert_eq!(
+            fixed_env.domain.d1, index.cs.domain.d1,
+            "the fixed environment was not built for the circuit of the index"
+        );
+
         // make sure that the SRS is not smaller than the domain size
         let d1_size = index.cs.domain.d1.size();
         if index.srs.max_degree() < d1_size {
             return Err(ProverError::SRSTooSmall);
         }
 
-        // TODO: rng should be passed as arg
-        let rng = &mut rand::rngs::OsRng;
-
         // double-check the witness
         if cfg!(debug_assertions) {
             let public = witness[0][0..index.cs.public].to_vec();
@@ -187,14 +311,27 @@ where
             w.extend(std::iter::repeat(G::ScalarField::zero()).take(length_padding));
 
             // zk-rows
-            for row in w.iter_mut().rev().take(ZK_ROWS as usize) {
-                *row = <G::ScalarField as UniformRand>::rand(rng);
+            if index.cs.zero_knowledge {
+                for row in w.iter_mut().rev().take(ZK_ROWS as usize) {
+                    *row = <G::ScalarField as UniformRand>::rand(rng);
+                }
             }
         }
 
         //~ 1. Setup the Fq-Sponge.
         let mut fq_sponge = EFqSponge::new(index.fq_sponge_params.clone());
 
+        //~ 1. Absorb the digest of the verifier index with the Fq-Sponge,
+        //~    to bind the proof to the circuit.
+        fq_sponge.absorb_fq(&[index.verifier_index_digest::<EFqSponge>()]);
+
+        //~ 1. Absorb the transcript prefix, if any, with the Fq-Sponge,
+        //~    preceded by its length so that different prefixes cannot lead to the same transcript.
+        if !transcript_prefix.is_empty() {
+            fq_sponge.absorb_fq(&[G::BaseField::from(transcript_prefix.len() as u64)]);
+            fq_sponge.absorb_fq(transcript_prefix);
+        }
+
         //~ 1. Compute the negated public input polynomial as
         //~    the polynomial that evaluates to $-p_i$ for the first `public_input_size` values of the domain,
         //~    and $0$ for the rest.
@@ -213,61 +350,55 @@ where
         //~    Note: unlike the original PLONK protocol,
         //~    the prover also provides evaluations of the public polynomial to help the verifier circuit.
         //~    This is why we need to absorb the commitment to the public polynomial at this point.
-        fq_sponge.absorb_g(&public_comm.unshifted);
+        fq_sponge.absorb_g_versioned(&public_comm.unshifted, index.cs.transcript_version);
 
         //~ 1. Commit to the witness columns by creating `COLUMNS` hidding commitments.
         //~
         //~    Note: since the witness is in evaluation form,
         //~    we can use the `commit_evaluation` optimization.
-        let mut w_comm = vec![];
-        for col in 0..COLUMNS {
-            // witness coeff -> witness eval
-            let witness_eval =
+        //~ 1. Compute the witness polynomials by interpolating each `COLUMNS` of the witness.
+        // The evaluations of the columns are kept after being committed to,
+        // and consumed in place by a single batch of interpolations rather than copied a second time.
+        let witness_evals: Vec<_> = witness
+            .iter()
+            .map(|w| {
                 Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(
-                    witness[col].clone(),
+                    w.clone(),
                     index.cs.domain.d1,
-                );
-
-            let com = match blinders.as_ref().and_then(|b| b[col].as_ref()) {
-                // no blinders: blind the witness
-                None => index
-                    .srs
-                    .commit_evaluations(index.cs.domain.d1, &witness_eval, None, rng),
-                // blinders: blind the witness with them
-                Some(blinder) => {
-                    // TODO: make this a function rather no? mask_with_custom()
-                    let witness_com = index.srs.commit_evaluations_non_hiding(
-                        index.cs.domain.d1,
-                        &witness_eval,
-                        None,
-                    );
-                    index
+                )
+            })
+            .collect();
+
+        // the columns are committed to in parallel, and blinded in order
+        let w_comm = index
+            .srs
+            .commit_evaluations_non_hiding_batch(index.cs.domain.d1, &witness_evals, None)
+            .into_iter()
+            .enumerate()
+            .map(
+                |(col, com)| match blinders.as_ref().and_then(|b| b[col].as_ref()) {
+                    // no blinders: blind the witness
+                    None => Ok(index.srs.mask(com, rng)),
+                    // blinders: blind the witness with them
+                    Some(blinder) => index
                         .srs
-                        .mask_custom(witness_com, blinder)
-                        .map_err(ProverError::WrongBlinders)?
-                }
-            };
-
-            w_comm.push(com);
-        }
+                        .mask_custom(com, blinder)
+                        .map_err(ProverError::WrongBlinders),
+                },
+            )
+            .collect::<Result<Vec<_>>>()?;
+        let witness_poly = interpolate_batch(witness_evals);
 
         let w_comm: [BlindedCommitment<G>; COLUMNS] = w_comm
             .try_into()
             .expect("previous loop is of the correct length");
+        let witness_poly: [DensePolynomial<G::ScalarField>; COLUMNS] = witness_poly
+            .try_into()
+            .expect("previous loop is of the correct length");
 
         //~ 1. Absorb the witness commitments with the Fq-Sponge.
-        w_comm
-            .iter()
-            .for_each(|c| fq_sponge.absorb_g(&c.commitment.unshifted));
-
-        //~ 1. Compute the witness polynomials by interpolating each `COLUMNS` of the witness.
-        //~    TODO: why not do this first, and then commit? Why commit from evaluation directly?
-        let witness_poly: [DensePolynomial<G::ScalarField>; COLUMNS] = array_init(|i| {
-            Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(
-                witness[i].clone(),
-                index.cs.domain.d1,
-            )
-            .interpolate()
+        w_comm.iter().for_each(|c| {
+            fq_sponge.absorb_g_versioned(&c.commitment.unshifted, index.cs.transcript_version)
         });
 
         let mut lookup_context = LookupContext::default();
@@ -305,8 +436,10 @@ where
                     }
 
                     // zero-knowledge
-                    for e in evals.iter_mut().rev().take(ZK_ROWS as usize) {
-                        *e = <G::ScalarField as UniformRand>::rand(rng);
+                    if index.cs.zero_knowledge {
+                        for e in evals.iter_mut().rev().take(ZK_ROWS as usize) {
+                            *e = <G::ScalarField as UniformRand>::rand(rng);
+                        }
                     }
 
                     // get coeff and evaluation form
@@ -324,7 +457,10 @@ where
                 let runtime_table_comm = index.srs.commit(&runtime_table_contribution, None, rng);
 
                 // absorb the commitment
-                fq_sponge.absorb_g(&runtime_table_comm.commitment.unshifted);
+                fq_sponge.absorb_g_versioned(
+                    &runtime_table_comm.commitment.unshifted,
+                    index.cs.transcript_version,
+                );
 
                 // pre-compute the updated second column of the lookup table
                 let mut second_column_d8 = runtime_table_contribution_d8.clone();
@@ -442,7 +578,14 @@ where
             //~~   in order to add zero-knowledge to the protocol.
             let sorted: Vec<_> = sorted
                 .into_iter()
-                .map(|chunk| lookup::constraints::zk_patch(chunk, index.cs.domain.d1, rng))
+                .map(|chunk| {
+                    lookup::constraints::zk_patch(
+                        chunk,
+                        index.cs.domain.d1,
+                        index.cs.zero_knowledge,
+                        rng,
+                    )
+                })
                 .collect();
 
             //~~ - Commit each of the sorted polynomials.
@@ -456,13 +599,13 @@ where
                 .collect();
 
             //~~ - Absorb each commitments to the sorted polynomials.
-            sorted_comms
-                .iter()
-                .for_each(|c| fq_sponge.absorb_g(&c.commitment.unshifted));
+            sorted_comms.iter().for_each(|c| {
+                fq_sponge.absorb_g_versioned(&c.commitment.unshifted, index.cs.transcript_version)
+            });
 
             // precompute different forms of the sorted polynomials for later
             // TODO: We can avoid storing these coefficients.
-            let sorted_coeffs: Vec<_> = sorted.iter().map(|e| e.clone().interpolate()).collect();
+            let sorted_coeffs = interpolate_batch(sorted.clone());
             let sorted8: Vec<_> = sorted_coeffs
                 .iter()
                 .map(|v| v.evaluate_over_domain_by_ref(index.cs.domain.d8))
@@ -499,6 +642,7 @@ where
                 beta,
                 gamma,
                 lookup_context.sorted.as_ref().unwrap(),
+                index.cs.zero_knowledge,
                 rng,
                 &lcs.configuration.lookup_info,
             )?;
@@ -509,7 +653,10 @@ where
                 .commit_evaluations(index.cs.domain.d1, &aggreg, None, rng);
 
             //~~ - Absorb the commitment to the aggregation polynomial with the Fq-Sponge.
-            fq_sponge.absorb_g(&aggreg_comm.commitment.unshifted);
+            fq_sponge.absorb_g_versioned(
+                &aggreg_comm.commitment.unshifted,
+                index.cs.transcript_version,
+            );
 
             // precompute different forms of the aggregation polynomial for later
             let aggreg_coeffs = aggreg.interpolate();
@@ -529,7 +676,7 @@ where
         let z_comm = index.srs.commit(&z_poly, None, rng);
 
         //~ 1. Absorb the permutation aggregation polynomial $z$ with the Fq-Sponge.
-        fq_sponge.absorb_g(&z_comm.commitment.unshifted);
+        fq_sponge.absorb_g_versioned(&z_comm.commitment.unshifted, index.cs.transcript_version);
 
         //~ 1. Sample $\alpha'$ with the Fq-Sponge.
         let alpha_chal = ScalarChallenge(fq_sponge.challenge());
@@ -566,47 +713,28 @@ where
 
         let lagrange = index.cs.evaluate(&witness_poly, &z_poly);
         let env = {
-            let mut index_evals = HashMap::new();
-            use GateType::*;
-            index_evals.insert(Poseidon, &index.cs.ps8);
-            index_evals.insert(CompleteAdd, &index.cs.complete_addl4);
-            index_evals.insert(VarBaseMul, &index.cs.mull8);
-            index_evals.insert(EndoMul, &index.cs.emull);
-            index_evals.insert(EndoMulScalar, &index.cs.endomul_scalar8);
-            [ChaCha0, ChaCha1, ChaCha2, ChaChaFinal]
-                .iter()
-                .enumerate()
-                .for_each(|(i, g)| {
-                    if let Some(c) = &index.cs.chacha8 {
-                        index_evals.insert(*g, &c[i]);
-                    }
-                });
-            if !index.cs.range_check_selector_polys.is_empty() {
-                index_evals.extend(range_check::circuit_gates().iter().enumerate().map(
-                    |(i, gate_type)| (*gate_type, &index.cs.range_check_selector_polys[i].eval8),
-                ));
-            }
-
-            Environment {
-                constants: Constants {
-                    alpha,
-                    beta,
-                    gamma,
-                    joint_combiner: lookup_context.joint_combiner,
-                    endo_coefficient: index.cs.endo,
-                    mds: index.cs.fr_sponge_params.mds.clone(),
-                },
-                witness: &lagrange.d8.this.w,
-                coefficient: &index.cs.coefficients8,
-                vanishes_on_last_4_rows: &index.cs.precomputations().vanishes_on_last_4_rows,
-                z: &lagrange.d8.this.z,
-                l0_1: l0_1(index.cs.domain.d1),
-                domain: index.cs.domain,
-                index: index_evals,
-                lookup: lookup_env,
-            }
+            let constants = Constants {
+                alpha,
+                beta,
+                gamma,
+                joint_combiner: lookup_context.joint_combiner,
+                endo_coefficient: index.cs.endo,
+                mds: index.cs.fr_sponge_params.mds.clone(),
+            };
+            fixed_env.with_witness(
+                &lagrange.d8.this.w,
+                &lagrange.d8.this.z,
+                constants,
+                lookup_env,
+            )
         };
 
+        // evaluations over the wrong domain would silently lead to a wrong quotient
+        if cfg!(debug_assertions) {
+            env.validate()
+                .expect("the polynomials of the environment should be over the expected domains");
+        }
+
         let quotient_poly = {
             // generic
             let alphas =
@@ -620,15 +748,6 @@ where
                 check_constraint!(index, gen_minus_pub);
             }
 
-            // complete addition
-            {
-                let add_constraint = CompleteAdd::combined_constraints(&all_alphas);
-                let add4 = add_constraint.evaluations(&env);
-                t4 += &add4;
-
-                check_constraint!(index, add4);
-            }
-
             // permutation
             let (mut t8, bnd) = {
                 let alphas =
@@ -642,91 +761,31 @@ where
                 (perm, bnd)
             };
 
-            if !index.cs.range_check_selector_polys.is_empty() {
-                // Range check gate
-                for gate_type in range_check::circuit_gates() {
-                    let expr = range_check::circuit_gate_constraints(gate_type, &all_alphas);
-
-                    let evals = expr.evaluations(&env);
-
-                    if evals.domain().size == t4.domain().size {
-                        t4 += &evals;
-                    } else if evals.domain().size == t8.domain().size {
-                        t8 += &evals;
-                    } else {
-                        panic!(
-                            "Bad evaluation domain size {} for {:?}",
-                            evals.domain().size,
-                            gate_type
-                        );
-                    }
-
-                    if cfg!(test) {
-                        let (_, res) = evals
-                            .interpolate()
-                            .divide_by_vanishing_poly(index.cs.domain.d1)
-                            .unwrap();
-                        if !res.is_zero() {
-                            panic!("Nonzero vanishing polynomial division for {:?}", gate_type);
-                        }
-                    }
+            // gates, except for the generic gate that is combined with the public input above
+            let registry = index.cs.constraint_registry();
+            for gate in registry.gates() {
+                if gate == GateType::Generic {
+                    continue;
                 }
-            }
-
-            // scalar multiplication
-            {
-                let mul8 = VarbaseMul::combined_constraints(&all_alphas).evaluations(&env);
-                t8 += &mul8;
-
-                check_constraint!(index, mul8);
-            }
-
-            // endoscaling
-            {
-                let emul8 = EndosclMul::combined_constraints(&all_alphas).evaluations(&env);
-                t8 += &emul8;
-
-                check_constraint!(index, emul8);
-            }
-
-            // endoscaling scalar computation
-            {
-                let emulscalar8 =
-                    EndomulScalar::combined_constraints(&all_alphas).evaluations(&env);
-                t8 += &emulscalar8;
-
-                check_constraint!(index, emulscalar8);
-            }
-
-            // poseidon
-            {
-                let pos8 = Poseidon::combined_constraints(&all_alphas).evaluations(&env);
-                t8 += &pos8;
-
-                check_constraint!(index, pos8);
-            }
 
-            // chacha
-            {
-                if index.cs.chacha8.as_ref().is_some() {
-                    let chacha0 = ChaCha0::combined_constraints(&all_alphas).evaluations(&env);
-                    t4 += &chacha0;
-
-                    let chacha1 = ChaCha1::combined_constraints(&all_alphas).evaluations(&env);
-                    t4 += &chacha1;
-
-                    let chacha2 = ChaCha2::combined_constraints(&all_alphas).evaluations(&env);
-                    t4 += &chacha2;
-
-                    let chacha_final =
-                        ChaChaFinal::combined_constraints(&all_alphas).evaluations(&env);
-                    t4 += &chacha_final;
-
-                    check_constraint!(index, chacha0);
-                    check_constraint!(index, chacha1);
-                    check_constraint!(index, chacha2);
-                    check_constraint!(index, chacha_final);
+                let evals = registry
+                    .combined_constraints(gate, &all_alphas)
+                    .expect("the gate is registered")
+                    .evaluations(&env);
+
+                if evals.domain().size == t4.domain().size {
+                    t4 += &evals;
+                } else if evals.domain().size == t8.domain().size {
+                    t8 += &evals;
+                } else {
+                    panic!(
+                        "Bad evaluation domain size {} for {:?}",
+                        evals.domain().size,
+                        gate
+                    );
                 }
+
+                check_constraint!(index, format!("{gate:?}"), evals);
             }
 
             // lookup
@@ -764,9 +823,7 @@ where
             f += &public_poly;
 
             // divide contributions with vanishing polynomial
-            let (mut quotient, res) = f
-                .divide_by_vanishing_poly(index.cs.domain.d1)
-                .ok_or(ProverError::Prover("division by vanishing polynomial"))?;
+            let (mut quotient, res) = f.divide_by_x_n_minus_1(index.cs.domain.d1.size());
             if !res.is_zero() {
                 return Err(ProverError::Prover(
                     "rest of division by vanishing polynomial",
@@ -777,29 +834,30 @@ where
             quotient
         };
 
-        //~ 1. commit (hiding) to the quotient polynomial $t$
-        //~    TODO: specify the dummies
+        //~ 1. Ensure that the degree of the quotient polynomial $t$ is less than `max_quot_size`,
+        //~    so that it fits in the expected number of commitment chunks.
+        let quotient_degree = quotient_poly.degree();
+        if quotient_degree >= index.max_quot_size {
+            return Err(ProverError::QuotientTooLarge {
+                degree: quotient_degree,
+                max: index.max_quot_size,
+            });
+        }
+
+        //~ 1. commit (hiding) to the quotient polynomial $t$,
+        //~    padding the commitment with hiding commitments to zero so that it always has
+        //~    $\max(\lceil \text{max\_quot\_size} / \text{max\_poly\_size} \rceil, \text{PERMUTS})$ chunks,
+        //~    whatever the degree of $t$.
         let t_comm = {
             let mut t_comm = index.srs.commit(&quotient_poly, None, rng);
-
-            let expected_t_size = PERMUTS;
-            let dummies = expected_t_size - t_comm.commitment.unshifted.len();
-            // Add `dummies` many hiding commitments to the 0 polynomial, since if the
-            // number of commitments in `t_comm` is less than the max size, it means that
-            // the higher degree coefficients of `t` are 0.
-            for _ in 0..dummies {
-                let w = <G::ScalarField as UniformRand>::rand(rng);
-                t_comm
-                    .commitment
-                    .unshifted
-                    .push(index.srs.h.mul(w).into_affine());
-                t_comm.blinders.unshifted.push(w);
-            }
+            let expected_t_size =
+                quotient_commitment_chunks(index.max_quot_size, index.max_poly_size);
+            pad_quotient_commitment(&index.srs, &mut t_comm, expected_t_size, rng);
             t_comm
         };
 
         //~ 1. Absorb the the commitment of the quotient polynomial with the Fq-Sponge.
-        fq_sponge.absorb_g(&t_comm.commitment.unshifted);
+        fq_sponge.absorb_g_versioned(&t_comm.commitment.unshifted, index.cs.transcript_version);
 
         //~ 1. Sample $\zeta'$ with the Fq-Sponge.
         let zeta_chal = ScalarChallenge(fq_sponge.challenge());
@@ -807,8 +865,7 @@ where
         //~ 1. Derive $\zeta$ from $\zeta'$ using the endomorphism (TODO: specify)
         let zeta = zeta_chal.to_field(&index.srs.endo_r);
 
-        let omega = index.cs.domain.d1.group_gen;
-        let zeta_omega = zeta * omega;
+        let (zeta, zeta_omega) = index.cs.domain.evaluation_points(zeta);
 
         //~ 1. If lookup is used, evaluate the following polynomials at $\zeta$ and $\zeta \omega$:
         if index.cs.lookup_constraint_system.is_some() {
@@ -873,105 +930,72 @@ where
         //~    $$(f_0(x), f_1(x), f_2(x), \ldots)$$
         //~
         //~    TODO: do we want to specify more on that? It seems unecessary except for the t polynomial (or if for some reason someone sets that to a low value)
-        let chunked_evals = {
-            let chunked_evals_zeta = ProofEvaluations::<Vec<G::ScalarField>> {
-                s: array_init(|i| {
-                    index.cs.sigmam[0..PERMUTS - 1][i]
-                        .to_chunked_polynomial(index.max_poly_size)
-                        .evaluate_chunks(zeta)
-                }),
-                w: array_init(|i| {
-                    witness_poly[i]
-                        .to_chunked_polynomial(index.max_poly_size)
-                        .evaluate_chunks(zeta)
-                }),
-
-                z: z_poly
-                    .to_chunked_polynomial(index.max_poly_size)
-                    .evaluate_chunks(zeta),
-
-                lookup: lookup_context.eval_zeta.take(),
-
-                generic_selector: index
-                    .cs
-                    .genericm
-                    .to_chunked_polynomial(index.max_poly_size)
-                    .evaluate_chunks(zeta),
+        let evaluation_points = [zeta, zeta_omega];
+        let chunked_evals: [ProofEvaluations<Vec<G::ScalarField>>; 2] = {
+            // the polynomials to evaluate, in the order:
+            // sigmas, witness columns, z, generic selector, poseidon selector
+            let polys: Vec<&DensePolynomial<G::ScalarField>> = index
+                .cs
+                .evaluated_sigmas()
+                .iter()
+                .chain(witness_poly.iter())
+                .chain([&z_poly, &index.cs.genericm, &index.cs.psm])
+                .collect();
 
-                poseidon_selector: index
-                    .cs
-                    .psm
-                    .to_chunked_polynomial(index.max_poly_size)
-                    .evaluate_chunks(zeta),
-            };
-            let chunked_evals_zeta_omega = ProofEvaluations::<Vec<G::ScalarField>> {
-                s: array_init(|i| {
-                    index.cs.sigmam[0..PERMUTS - 1][i]
-                        .to_chunked_polynomial(index.max_poly_size)
-                        .evaluate_chunks(zeta_omega)
-                }),
-
-                w: array_init(|i| {
-                    witness_poly[i]
-                        .to_chunked_polynomial(index.max_poly_size)
-                        .evaluate_chunks(zeta_omega)
-                }),
-
-                z: z_poly
-                    .to_chunked_polynomial(index.max_poly_size)
-                    .evaluate_chunks(zeta_omega),
-
-                lookup: lookup_context.eval_zeta_omega.take(),
-
-                generic_selector: index
-                    .cs
-                    .genericm
-                    .to_chunked_polynomial(index.max_poly_size)
-                    .evaluate_chunks(zeta_omega),
+            // these evaluations are independent, so we compute them in parallel
+            let max_poly_size = index.max_poly_size;
+            let evals_per_poly: Vec<[Vec<G::ScalarField>; 2]> = polys
+                .into_par_iter()
+                .map(|poly| {
+                    let chunked = poly.to_chunked_polynomial(max_poly_size);
+                    evaluation_points.map(|pt| chunked.evaluate_chunks(pt))
+                })
+                .collect();
 
-                poseidon_selector: index
-                    .cs
-                    .psm
-                    .to_chunked_polynomial(index.max_poly_size)
-                    .evaluate_chunks(zeta_omega),
-            };
+            let to_proof_evaluations =
+                |evals: Vec<Vec<G::ScalarField>>,
+                 lookup: Option<LookupEvaluations<Vec<G::ScalarField>>>| {
+                    let mut evals = evals.into_iter();
+                    let s = array_init(|_| evals.next().unwrap());
+                    let w = array_init(|_| evals.next().unwrap());
+                    let z = evals.next().unwrap();
+                    let generic_selector = evals.next().unwrap();
+                    let poseidon_selector = evals.next().unwrap();
+                    ProofEvaluations {
+                        s,
+                        w,
+                        z,
+                        lookup,
+                        generic_selector,
+                        poseidon_selector,
+                    }
+                };
 
-            [chunked_evals_zeta, chunked_evals_zeta_omega]
+            let mut lookup_evals = [
+                lookup_context.eval_zeta.take(),
+                lookup_context.eval_zeta_omega.take(),
+            ];
+            array_init(|i| {
+                to_proof_evaluations(
+                    evals_per_poly
+                        .iter()
+                        .map(|evals| evals[i].clone())
+                        .collect(),
+                    lookup_evals[i].take(),
+                )
+            })
         };
 
         let zeta_to_srs_len = zeta.pow(&[index.max_poly_size as u64]);
-        let zeta_omega_to_srs_len = zeta.pow(&[index.max_poly_size as u64]);
         let zeta_to_domain_size = zeta.pow(&[d1_size as u64]);
 
         //~ 1. Evaluate the same polynomials without chunking them
         //~    (so that each polynomial should correspond to a single value this time).
-        let evals = {
-            let power_of_eval_points_for_chunks = [zeta_to_srs_len, zeta_omega_to_srs_len];
-            &chunked_evals
-                .iter()
-                .zip(power_of_eval_points_for_chunks.iter()) // (zeta , zeta_omega)
-                .map(|(es, &e1)| ProofEvaluations::<G::ScalarField> {
-                    s: array_init(|i| DensePolynomial::eval_polynomial(&es.s[i], e1)),
-                    w: array_init(|i| DensePolynomial::eval_polynomial(&es.w[i], e1)),
-                    z: DensePolynomial::eval_polynomial(&es.z, e1),
-                    lookup: es.lookup.as_ref().map(|l| LookupEvaluations {
-                        table: DensePolynomial::eval_polynomial(&l.table, e1),
-                        aggreg: DensePolynomial::eval_polynomial(&l.aggreg, e1),
-                        sorted: l
-                            .sorted
-                            .iter()
-                            .map(|p| DensePolynomial::eval_polynomial(p, e1))
-                            .collect(),
-                        runtime: l
-                            .runtime
-                            .as_ref()
-                            .map(|p| DensePolynomial::eval_polynomial(p, e1)),
-                    }),
-                    generic_selector: DensePolynomial::eval_polynomial(&es.generic_selector, e1),
-                    poseidon_selector: DensePolynomial::eval_polynomial(&es.poseidon_selector, e1),
-                })
-                .collect::<Vec<_>>()
-        };
+        let evals = &chunked_evals
+            .iter()
+            .zip(evaluation_points)
+            .map(|(es, pt)| es.combine_at(pt, index.max_poly_size))
+            .collect::<Vec<_>>();
 
         //~ 1. Compute the ft polynomial.
         //~    This is to implement [Maller's optimization](https://o1-labs.github.io/mina-book/crypto/plonk/maller_15.html).
@@ -981,18 +1005,10 @@ where
                 // that we can drop the coefficient forms of the index polynomials from
                 // the constraint system struct
 
-                // generic (not part of linearization yet)
-                let alphas = all_alphas
-                    .get_alphas(ArgumentType::Gate(GateType::Generic), generic::CONSTRAINTS);
-                let mut f = index
-                    .cs
-                    .gnrc_lnrz(alphas, &evals[0].w, evals[0].generic_selector)
-                    .interpolate();
-
                 // permutation (not part of linearization yet)
                 let alphas =
                     all_alphas.get_alphas(ArgumentType::Permutation, permutation::CONSTRAINTS);
-                f += &index.cs.perm_lnrz(evals, zeta, beta, gamma, alphas);
+                let f = index.cs.perm_lnrz(evals, zeta, beta, gamma, alphas);
 
                 // the circuit polynomial
                 let f = {
@@ -1040,12 +1056,13 @@ where
         fr_sponge.absorb(&fq_sponge.digest());
 
         //~ 1. Evaluate the negated public polynomial (if present) at $\zeta$ and $\zeta\omega$.
-        let public_evals = if public_poly.is_zero() {
+        let public_evals = if public.is_empty() {
             [Vec::new(), Vec::new()]
         } else {
+            let d1 = index.cs.domain.d1;
             [
-                vec![public_poly.evaluate(&zeta)],
-                vec![public_poly.evaluate(&zeta_omega)],
+                vec![-generic::eval_public_input(&public, d1, zeta)],
+                vec![-generic::eval_public_input(&public, d1, zeta_omega)],
             ]
         };
 
@@ -1056,12 +1073,8 @@ where
         //~~ - poseidon selector
         //~~ - the 15 register/witness
         //~~ - 6 sigmas evaluations (the last one is not evaluated)
-        for i in 0..2 {
-            fr_sponge.absorb_evaluations(&public_evals[i], &chunked_evals[i])
-        }
-
         //~ 1. Absorb the unique evaluation of ft: $ft(\zeta\omega)$.
-        fr_sponge.absorb(&ft_eval1);
+        fr_sponge.absorb_proof_scalars(&public_evals, &chunked_evals, &ft_eval1);
 
         //~ 1. Sample $v'$ with the Fr-Sponge
         let v_chal = fr_sponge.challenge();
@@ -1173,7 +1186,7 @@ where
         let proof = index.srs.open(
             group_map,
             &polynomials,
-            &[zeta, zeta_omega],
+            &evaluation_points,
             v,
             u,
             fq_sponge_before_evaluations,
@@ -1189,7 +1202,8 @@ where
                 runtime: lookup_context.runtime_table_comm.map(|x| x.commitment),
             });
 
-        Ok(Self {
+        let joint_combiner = lookup_context.joint_combiner;
+        let prover_proof = Self {
             commitments: ProverCommitments {
                 w_comm: array_init(|i| w_comm[i].commitment.clone()),
                 z_comm: z_comm.commitment,
@@ -1201,7 +1215,40 @@ where
             ft_eval1,
             public,
             prev_challenges,
-        })
+        };
+
+        // with the `check_transcript` feature, replay the transcript of the proof as the verifier does,
+        // and check that it derives the same challenges as the ones used above.
+        // This builds a verifier index for each proof, so it is only meant for debugging the prover.
+        if cfg!(feature = "check_transcript") {
+            let replayed = prover_proof
+                .oracles::<EFqSponge, EFrSponge>(
+                    &index.verifier_index(),
+                    &public_comm,
+                    transcript_prefix,
+                )
+                .expect("the transcript of the proof could not be replayed")
+                .oracles;
+            assert_eq!(
+                [
+                    replayed.beta,
+                    replayed.gamma,
+                    replayed.alpha,
+                    replayed.zeta,
+                    replayed.v,
+                    replayed.u
+                ],
+                [beta, gamma, alpha, zeta, v, u],
+                "the transcript of the proof diverges from the one of the prover"
+            );
+            assert_eq!(
+                replayed.joint_combiner.map(|(_, j)| j),
+                joint_combiner,
+                "the joint combiner of the proof diverges from the one of the prover"
+            );
+        }
+
+        Ok(prover_proof)
     }
 }
 
@@ -10,7 +10,8 @@ use crate::linearization::expr_linearization;
 use ark_ff::PrimeField;
 use ark_poly::EvaluationDomain;
 use commitment_dlog::{commitment::CommitmentCurve, srs::SRS};
-use oracle::poseidon::ArithmeticSpongeParams;
+use once_cell::sync::OnceCell;
+use oracle::{poseidon::ArithmeticSpongeParams, FqSponge};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_with::serde_as;
 use std::sync::Arc;
@@ -33,6 +34,13 @@ pub struct ProverIndex<G: CommitmentCurve> {
     pub powers_of_alpha: Alphas<G::ScalarField>,
 
     /// polynomial commitment keys
+    ///
+    /// Committing to a polynomial only uses as many bases as it has coefficients,
+    /// and the bases of [SRS::create] do not depend on its size,
+    /// so a single large SRS can be shared between the indices of circuits of different sizes
+    /// without slowing down the commitments of the smaller ones.
+    /// The cost of sharing it is memory: the whole SRS is kept alive,
+    /// along with a Lagrange basis for each domain size that uses it.
     #[serde(skip)]
     pub srs: Arc<SRS<G>>,
 
@@ -45,6 +53,11 @@ pub struct ProverIndex<G: CommitmentCurve> {
     /// random oracle argument parameters
     #[serde(skip)]
     pub fq_sponge_params: ArithmeticSpongeParams<G::BaseField>,
+
+    /// the digest of the verifier index, computed the first time it is needed
+    /// (see [ProverIndex::verifier_index_digest])
+    #[serde(skip)]
+    pub verifier_index_digest: OnceCell<G::BaseField>,
 }
 //~spec:endcode
 
@@ -91,8 +104,21 @@ where
             max_poly_size,
             max_quot_size,
             fq_sponge_params,
+            verifier_index_digest: OnceCell::new(),
         }
     }
+
+    /// Returns the digest of the corresponding [VerifierIndex](crate::verifier_index::VerifierIndex).
+    /// Computing it requires committing to all the fixed polynomials of the circuit,
+    /// so it is only done the first time, including after deserializing the index,
+    /// and cached for the next proofs.
+    pub fn verifier_index_digest<EFqSponge: FqSponge<G::BaseField, G, G::ScalarField>>(
+        &self,
+    ) -> G::BaseField {
+        *self
+            .verifier_index_digest
+            .get_or_init(|| self.verifier_index().digest::<EFqSponge>())
+    }
 }
 
 pub mod testing {
@@ -142,6 +142,7 @@ impl TestRunner {
             &prover,
             self.0.recursion,
             None,
+            &[],
         )
         .unwrap();
         println!("- time to create proof: {:?}s", start.elapsed().as_secs());
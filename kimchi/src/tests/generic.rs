@@ -1,22 +1,75 @@
 use super::framework::TestFramework;
+use crate::circuits::gate::CircuitGate;
 use crate::circuits::polynomials::generic::testing::{create_circuit, fill_in_witness};
-use crate::circuits::wires::COLUMNS;
-use ark_ff::Zero;
+use crate::circuits::polynomials::generic::GenericGateSpec;
+use crate::circuits::polynomials::permutation::ZK_ROWS;
+use crate::circuits::wires::{Wire, COLUMNS, PERMUTS};
+use crate::error::{ProverError, VerifyError};
+use crate::proof::ProverProof;
+use crate::prover::{pad_quotient_commitment, WitnessLayout};
+use crate::prover_index::{testing::new_index_for_test, ProverIndex};
+use crate::verifier::{verify, verify_with_prefix};
+use crate::verifier_index::{CommitmentChunks, VerifierIndex};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{One, UniformRand, Zero};
+use ark_poly::{univariate::DensePolynomial, EvaluationDomain, UVPolynomial};
 use array_init::array_init;
-use mina_curves::pasta::fp::Fp;
+use commitment_dlog::commitment::CommitmentCurve;
+use groupmap::GroupMap;
+use mina_curves::pasta::{
+    fp::Fp,
+    fq::Fq,
+    vesta::{Affine, VestaParameters},
+};
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+    TranscriptVersion,
+};
+use rand::prelude::*;
 
-#[test]
-fn test_generic_gate() {
-    let gates = create_circuit(0, 0);
+type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+/// A witness for the circuit created by [create_circuit] with `public` inputs.
+fn witness(public: &[Fp]) -> [Vec<Fp>; COLUMNS] {
+    let rows = create_circuit::<Fp>(0, public.len()).len();
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); rows]);
+    fill_in_witness(0, &mut witness, public);
+    witness
+}
+
+/// Creates the indexes of the circuit created by [create_circuit] with `public` inputs,
+/// the group map, and a witness for the circuit.
+fn setup(
+    public: &[Fp],
+) -> (
+    ProverIndex<Affine>,
+    VerifierIndex<Affine>,
+    <Affine as CommitmentCurve>::Map,
+    [Vec<Fp>; COLUMNS],
+) {
+    let index = new_index_for_test(create_circuit(0, public.len()), public.len());
+    let verifier_index = index.verifier_index();
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    (index, verifier_index, group_map, witness(public))
+}
 
-    // create witness
-    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); gates.len()]);
-    fill_in_witness(0, &mut witness, &[]);
+/// Creates a proof for `witness`.
+fn prove(
+    index: &ProverIndex<Affine>,
+    group_map: &<Affine as CommitmentCurve>::Map,
+    witness: [Vec<Fp>; COLUMNS],
+) -> ProverProof<Affine> {
+    ProverProof::create::<BaseSponge, ScalarSponge>(group_map, witness, &[], index).unwrap()
+}
 
+#[test]
+fn test_generic_gate() {
     // create and verify proof based on the witness
     TestFramework::default()
-        .gates(gates)
-        .witness(witness)
+        .gates(create_circuit(0, 0))
+        .witness(witness(&[]))
         .setup()
         .prove_and_verify();
 }
@@ -24,17 +77,347 @@ fn test_generic_gate() {
 #[test]
 fn test_generic_gate_pub() {
     let public = vec![Fp::from(3u8); 5];
-    let gates = create_circuit(0, public.len());
-
-    // create witness
-    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); gates.len()]);
-    fill_in_witness(0, &mut witness, &public);
 
     // create and verify proof based on the witness
     TestFramework::default()
-        .gates(gates)
-        .witness(witness)
+        .gates(create_circuit(0, public.len()))
+        .witness(witness(&public))
         .public_inputs(public)
         .setup()
         .prove_and_verify();
 }
+
+#[test]
+fn test_generic_gate_pub_tampered() {
+    let (index, verifier_index, group_map, witness) = setup(&[Fp::from(3u8); 5]);
+
+    let mut proof = prove(&index, &group_map, witness);
+    verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof).unwrap();
+
+    // the verifier must re-derive the same transcript as the prover,
+    // so a proof for different public inputs must not verify
+    proof.public[0] += Fp::one();
+    assert!(
+        verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof).is_err()
+    );
+}
+
+#[test]
+fn test_generic_gate_flat_witness() {
+    let public = vec![Fp::from(3u8); 5];
+    let (index, verifier_index, group_map, witness) = setup(&public);
+
+    let n = witness[0].len();
+    let column_major: Vec<Fp> = witness.iter().flatten().copied().collect();
+    let row_major: Vec<Fp> = (0..n)
+        .flat_map(|row| witness.iter().map(move |col| col[row]))
+        .collect();
+
+    for (flat, layout) in [
+        (&column_major, WitnessLayout::ColumnMajor),
+        (&row_major, WitnessLayout::RowMajor),
+    ] {
+        let proof = ProverProof::create_from_flat::<BaseSponge, ScalarSponge>(
+            &group_map,
+            flat,
+            layout,
+            &[],
+            &index,
+        )
+        .unwrap();
+        assert_eq!(proof.public, public);
+        verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof).unwrap();
+    }
+
+    // the flat witness must hold a whole number of rows
+    assert!(matches!(
+        ProverProof::create_from_flat::<BaseSponge, ScalarSponge>(
+            &group_map,
+            &row_major[1..],
+            WitnessLayout::RowMajor,
+            &[],
+            &index,
+        ),
+        Err(ProverError::FlatWitnessLength(len)) if len == COLUMNS * n - 1
+    ));
+}
+
+#[test]
+fn test_generic_gate_pub_count_mismatch() {
+    let public = vec![Fp::from(3u8); 5];
+    let (index, verifier_index, group_map, witness) = setup(&public);
+    assert_eq!(verifier_index.public, Some(public.len()));
+
+    let mut proof = prove(&index, &group_map, witness);
+    assert_eq!(proof.public, public);
+
+    proof.public.pop();
+    assert!(matches!(
+        verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof),
+        Err(VerifyError::PublicInputCountMismatch {
+            got: 4,
+            expected: 5
+        })
+    ));
+}
+
+#[test]
+fn test_generic_gate_commitment_chunk_counts() {
+    let (index, verifier_index, group_map, witness) = setup(&[Fp::from(3u8); 5]);
+    let chunks = verifier_index.commitment_chunk_counts();
+    assert_eq!(
+        chunks,
+        CommitmentChunks {
+            w: 1,
+            z: 1,
+            t: PERMUTS
+        }
+    );
+
+    let proof = prove(&index, &group_map, witness);
+    for w_comm in &proof.commitments.w_comm {
+        assert!(w_comm.unshifted.len() <= chunks.w);
+    }
+    assert_eq!(proof.commitments.z_comm.unshifted.len(), chunks.z);
+    assert_eq!(proof.commitments.t_comm.unshifted.len(), chunks.t);
+}
+
+#[test]
+fn test_generic_gate_t_comm_size_independent_of_witness() {
+    let (index, verifier_index, group_map, _) = setup(&[Fp::zero(); 5]);
+    let chunks = verifier_index.commitment_chunk_counts();
+    let rng = &mut StdRng::from_seed([0; 32]);
+
+    // quotient polynomials of different degrees have commitments with different numbers of chunks...
+    let low = DensePolynomial::from_coefficients_vec(vec![Fp::one()]);
+    let high = DensePolynomial::<Fp>::rand(chunks.t * index.max_poly_size - 1, rng);
+    let mut low_comm = index.srs.commit(&low, None, rng);
+    let mut high_comm = index.srs.commit(&high, None, rng);
+    assert_eq!(low_comm.commitment.unshifted.len(), 1);
+    assert_eq!(high_comm.commitment.unshifted.len(), chunks.t);
+
+    // ...but the prover pads them to the same number of chunks, with hiding commitments to zero
+    pad_quotient_commitment(&index.srs, &mut low_comm, chunks.t, rng);
+    pad_quotient_commitment(&index.srs, &mut high_comm, chunks.t, rng);
+    assert_eq!(low_comm.commitment.unshifted.len(), chunks.t);
+    assert_eq!(high_comm.commitment.unshifted.len(), chunks.t);
+    for (comm, blinder) in low_comm
+        .commitment
+        .unshifted
+        .iter()
+        .zip(&low_comm.blinders.unshifted)
+        .skip(1)
+    {
+        assert_eq!(*comm, index.srs.h.mul(*blinder).into_affine());
+    }
+
+    // and so do proofs for different witnesses
+    for public in [[Fp::zero(); 5], [Fp::from(3u8); 5]] {
+        let proof = prove(&index, &group_map, witness(&public));
+        assert_eq!(proof.commitments.t_comm.unshifted.len(), chunks.t);
+        verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof).unwrap();
+    }
+}
+
+#[test]
+fn test_generic_gate_verifier_index_digest() {
+    let (index, verifier_index, group_map, witness) = setup(&[]);
+
+    // the digest is computed once, and then cached by both indexes
+    assert!(verifier_index.digest.get().is_none());
+    let digest = verifier_index.digest::<BaseSponge>();
+    assert_eq!(verifier_index.digest.get(), Some(&digest));
+    assert!(index.verifier_index_digest.get().is_none());
+    assert_eq!(index.verifier_index_digest::<BaseSponge>(), digest);
+    assert_eq!(index.verifier_index_digest.get(), Some(&digest));
+
+    // a different circuit has a different digest
+    let (_, other_verifier_index, _, _) = setup(&[Fp::zero()]);
+    assert_ne!(other_verifier_index.digest::<BaseSponge>(), digest);
+
+    // proofs are created with the cached digest
+    let proof = prove(&index, &group_map, witness);
+    verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof).unwrap();
+}
+
+#[test]
+fn test_generic_gate_transcript_prefix() {
+    let (index, verifier_index, group_map, witness) = setup(&[]);
+
+    let prefix = [Fq::from(1u64), Fq::from(2u64)];
+    let proof = ProverProof::create_recursive::<BaseSponge, ScalarSponge>(
+        &group_map,
+        witness,
+        &[],
+        &index,
+        vec![],
+        None,
+        &prefix,
+    )
+    .unwrap();
+
+    // the verifier must absorb the same prefix
+    verify_with_prefix::<Affine, BaseSponge, ScalarSponge>(
+        &group_map,
+        &verifier_index,
+        &proof,
+        &prefix,
+    )
+    .unwrap();
+    assert!(verify_with_prefix::<Affine, BaseSponge, ScalarSponge>(
+        &group_map,
+        &verifier_index,
+        &proof,
+        &prefix[..1],
+    )
+    .is_err());
+    assert!(verify_with_prefix::<Affine, BaseSponge, ScalarSponge>(
+        &group_map,
+        &verifier_index,
+        &proof,
+        &[prefix[0], prefix[1], Fq::zero()],
+    )
+    .is_err());
+    assert!(
+        verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof).is_err()
+    );
+}
+
+#[test]
+fn test_generic_gate_missing_chunk() {
+    let (index, verifier_index, group_map, witness) = setup(&[]);
+    let mut proof = prove(&index, &group_map, witness);
+
+    // the witness commitments are made of a single chunk, so must be their evaluations
+    proof.evals[0].w[0].clear();
+    assert!(matches!(
+        verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof),
+        Err(VerifyError::ChunkCountMismatch("witness"))
+    ));
+}
+
+#[test]
+fn test_generic_gate_no_zero_knowledge() {
+    // only the prover depends on the flag, the verifier index is the same
+    let (mut index, verifier_index, group_map, witness) = setup(&[Fp::from(3u8); 5]);
+    index.cs.zero_knowledge = false;
+
+    let proof = prove(&index, &group_map, witness);
+    verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof).unwrap();
+}
+
+#[test]
+fn test_generic_gate_compressed_transcript() {
+    let (mut index, _, group_map, witness) = setup(&[Fp::from(3u8); 5]);
+    index.cs.transcript_version = TranscriptVersion::V1;
+    let mut verifier_index = index.verifier_index();
+
+    let proof = prove(&index, &group_map, witness);
+    verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof).unwrap();
+
+    // the verifier must absorb the commitments the same way as the prover
+    verifier_index.transcript_version = TranscriptVersion::V0;
+    assert!(
+        verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof).is_err()
+    );
+}
+
+#[test]
+fn test_generic_gate_random_domain_sizes() {
+    let rng = &mut StdRng::from_seed([42u8; 32]);
+
+    for log_size in 4..=12 {
+        // fill the domain exactly, leaving room for the zero-knowledge rows
+        let rows = (1 << log_size) - ZK_ROWS as usize;
+        let public_len = rng.gen_range(0..4);
+
+        let mut gates = vec![];
+        let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); rows]);
+        let public: Vec<Fp> = (0..public_len).map(|_| Fp::rand(rng)).collect();
+
+        for (row, p) in public.iter().enumerate() {
+            gates.push(CircuitGate::create_generic_gadget(
+                Wire::new(row),
+                GenericGateSpec::Pub,
+                None,
+            ));
+            witness[0][row] = *p;
+        }
+
+        for row in public_len..rows {
+            let (left, right, mul) = (Fp::rand(rng), Fp::rand(rng), Fp::rand(rng));
+            gates.push(CircuitGate::create_generic_gadget(
+                Wire::new(row),
+                GenericGateSpec::Add {
+                    left_coeff: Some(left),
+                    right_coeff: Some(right),
+                    output_coeff: None,
+                },
+                Some(GenericGateSpec::Mul {
+                    output_coeff: None,
+                    mul_coeff: Some(mul),
+                }),
+            ));
+
+            // left * w0 + right * w1 = w2
+            witness[0][row] = Fp::rand(rng);
+            witness[1][row] = Fp::rand(rng);
+            witness[2][row] = left * witness[0][row] + right * witness[1][row];
+
+            // mul * w3 * w4 = w5
+            witness[3][row] = Fp::rand(rng);
+            witness[4][row] = Fp::rand(rng);
+            witness[5][row] = mul * witness[3][row] * witness[4][row];
+        }
+
+        let runner = TestFramework::default()
+            .gates(gates)
+            .witness(witness)
+            .public_inputs(public)
+            .setup();
+        assert_eq!(runner.prover_index().cs.domain.d1.size(), 1 << log_size);
+        runner.prove_and_verify();
+    }
+}
+
+#[test]
+fn test_generic_gate_shared_fixed_environment() {
+    let (index, verifier_index, group_map, _) = setup(&[Fp::zero()]);
+
+    // one environment for several proofs against the same circuit
+    let fixed_env = index.cs.fixed_environment();
+    for public in [Fp::from(3u8), Fp::from(7u8)] {
+        let witness = witness(&[public]);
+
+        let proof = ProverProof::create_recursive_with_environment::<BaseSponge, ScalarSponge, _>(
+            &group_map,
+            witness.clone(),
+            &[],
+            &index,
+            &fixed_env,
+            vec![],
+            None,
+            &[],
+            &mut StdRng::from_seed([0u8; 32]),
+        )
+        .unwrap();
+        verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof).unwrap();
+
+        // same proof as with a freshly built environment
+        let expected = ProverProof::create_recursive_with_rng::<BaseSponge, ScalarSponge, _>(
+            &group_map,
+            witness,
+            &[],
+            &index,
+            vec![],
+            None,
+            &[],
+            &mut StdRng::from_seed([0u8; 32]),
+        )
+        .unwrap();
+        assert_eq!(
+            rmp_serde::to_vec(&proof).unwrap(),
+            rmp_serde::to_vec(&expected).unwrap()
+        );
+    }
+}
@@ -1,12 +1,14 @@
 use crate::bench::BenchmarkCtx;
+use crate::circuits::constraints::FixedPolynomials;
 use crate::circuits::polynomials::generic::testing::{create_circuit, fill_in_witness};
-use crate::circuits::wires::COLUMNS;
+use crate::circuits::wires::{COLUMNS, PERMUTS};
 use crate::proof::ProverProof;
 use crate::prover_index::testing::new_index_for_test;
 use crate::verifier::verify;
 use crate::verifier_index::VerifierIndex;
 use ark_ec::short_weierstrass_jacobian::GroupAffine;
 use ark_ff::Zero;
+use ark_poly::{EvaluationDomain, Evaluations};
 use array_init::array_init;
 use commitment_dlog::commitment::CommitmentCurve;
 use commitment_dlog::srs::SRS;
@@ -15,6 +17,7 @@ use mina_curves::pasta::fp::Fp;
 use mina_curves::pasta::vesta::{Affine, VestaParameters};
 use oracle::constants::PlonkSpongeConstantsKimchi;
 use oracle::sponge::{DefaultFqSponge, DefaultFrSponge};
+use rand::{rngs::StdRng, SeedableRng};
 use std::time::Instant;
 
 type SpongeParams = PlonkSpongeConstantsKimchi;
@@ -85,5 +88,101 @@ mod tests {
         verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &verifier_index_deserialize, &proof)
             .unwrap();
         println!("- time to verify: {}ms", start.elapsed().as_millis());
+
+        // verifier indexes serialized before the number of public inputs was recorded can still be read
+        let mut old_format: serde_json::Value =
+            serde_json::from_str(&verifier_index_serialize).unwrap();
+        old_format.as_object_mut().unwrap().remove("public");
+        let old_index: VerifierIndex<GroupAffine<VestaParameters>> =
+            serde_json::from_value(old_format).unwrap();
+        assert_eq!(verifier_index_deserialize.public, Some(public.len()));
+        assert_eq!(old_index.public, None);
+    }
+
+    #[test]
+    fn test_export_fixed_polynomials() {
+        let gates = create_circuit(0, 0);
+        let index = new_index_for_test(gates, 0);
+        let verifier_index = index.verifier_index();
+
+        let fixed = index.cs.export_fixed_polynomials();
+        let serialized = serde_json::to_string(&fixed).unwrap();
+        let fixed: FixedPolynomials<Fp> = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(fixed, index.cs.export_fixed_polynomials());
+
+        // the commitments of the verifier index can be recomputed from the exported polynomials
+        let d1 = verifier_index.domain;
+        assert_eq!(fixed.domain_size, d1.size);
+        let commit = |evals: &Vec<Fp>| {
+            let evals = Evaluations::from_vec_and_domain(evals.clone(), d1);
+            index
+                .srs
+                .commit_evaluations_non_hiding(d1, &evals, None)
+                .unshifted
+        };
+        assert_eq!(fixed.sigma.len(), PERMUTS);
+        for (evals, comm) in fixed.sigma.iter().zip(&verifier_index.sigma_comm) {
+            assert_eq!(commit(evals), comm.unshifted);
+        }
+        assert_eq!(fixed.coefficients.len(), COLUMNS);
+        for (evals, comm) in fixed
+            .coefficients
+            .iter()
+            .zip(&verifier_index.coefficients_comm)
+        {
+            assert_eq!(commit(evals), comm.unshifted);
+        }
+        assert_eq!(
+            commit(&fixed.generic),
+            verifier_index.generic_comm.unshifted
+        );
+        assert_eq!(commit(&fixed.poseidon), verifier_index.psm_comm.unshifted);
+        assert_eq!(
+            commit(&fixed.complete_add),
+            verifier_index.complete_add_comm.unshifted
+        );
+        assert_eq!(
+            commit(&fixed.var_base_mul),
+            verifier_index.mul_comm.unshifted
+        );
+        assert_eq!(commit(&fixed.endo_mul), verifier_index.emul_comm.unshifted);
+        assert_eq!(
+            commit(&fixed.endo_mul_scalar),
+            verifier_index.endomul_scalar_comm.unshifted
+        );
+        assert!(fixed.chacha.is_none());
+        assert!(fixed.range_check.is_empty());
+        assert_eq!(d1.size(), fixed.generic.len());
+    }
+
+    /// Creates a proof twice with the same seeded rng, and checks that the serialized proofs are equal.
+    #[test]
+    fn test_seeded_proof() {
+        let public = vec![Fp::from(3u8); 5];
+        let gates = create_circuit(0, public.len());
+
+        // create witness
+        let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); gates.len()]);
+        fill_in_witness(0, &mut witness, &public);
+
+        let index = new_index_for_test(gates, public.len());
+        let group_map = <Affine as CommitmentCurve>::Map::setup();
+        let create_proof = || {
+            let proof = ProverProof::create_recursive_with_rng::<BaseSponge, ScalarSponge, _>(
+                &group_map,
+                witness.clone(),
+                &[],
+                &index,
+                vec![],
+                None,
+                &[],
+                &mut StdRng::from_seed([0u8; 32]),
+            )
+            .unwrap();
+            rmp_serde::to_vec(&proof).unwrap()
+        };
+
+        // the same seed gives the same proof
+        assert_eq!(create_proof(), create_proof());
     }
 }
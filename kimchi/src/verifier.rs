@@ -5,7 +5,7 @@ use crate::{
     circuits::{
         argument::ArgumentType,
         constraints::ConstraintSystem,
-        expr::{Column, Constants, PolishToken},
+        expr::{linearization_commitment, Column, Constants, PolishToken},
         gate::GateType,
         lookup::{lookups::LookupsUsed, tables::combine_table},
         polynomials::{generic, permutation},
@@ -14,11 +14,12 @@ use crate::{
     },
     error::VerifyError,
     plonk_sponge::FrSponge,
-    proof::{ProverProof, RecursionChallenge},
+    proof::{LookupEvaluations, ProofEvaluations, ProverProof, RecursionChallenge},
     verifier_index::VerifierIndex,
 };
 use ark_ff::{Field, One, PrimeField, Zero};
 use ark_poly::{EvaluationDomain, Polynomial};
+use array_init::array_init;
 use commitment_dlog::commitment::{
     b_poly, b_poly_coefficients, combined_inner_product, BatchEvaluationProof, CommitmentCurve,
     Evaluation, PolyComm,
@@ -26,6 +27,7 @@ use commitment_dlog::commitment::{
 use itertools::izip;
 use oracle::{sponge::ScalarChallenge, FqSponge};
 use rand::thread_rng;
+use std::collections::HashMap;
 
 /// The result of a proof verification.
 pub type Result<T> = std::result::Result<T, VerifyError>;
@@ -108,7 +110,8 @@ where
             .collect()
     }
 
-    /// This function runs the random oracle argument
+    /// This function runs the random oracle argument,
+    /// after absorbing the `transcript_prefix` the proof was created with
     pub fn oracles<
         EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
         EFrSponge: FrSponge<G::ScalarField>,
@@ -116,6 +119,7 @@ where
         &self,
         index: &VerifierIndex<G>,
         p_comm: &PolyComm<G>,
+        transcript_prefix: &[G::BaseField],
     ) -> Result<OraclesResult<G, EFqSponge>> {
         //~
         //~ #### Fiat-Shamir argument
@@ -124,17 +128,47 @@ where
         //~
         let n = index.domain.size;
 
+        //~ 1. Check that the proof has as many public inputs as the circuit,
+        //~    if the verifier index records it.
+        if let Some(expected) = index.public {
+            if self.public.len() != expected {
+                return Err(VerifyError::PublicInputCountMismatch {
+                    got: self.public.len(),
+                    expected,
+                });
+            }
+        }
+
+        //~ 1. Check that the commitments of the proof have no more chunks than a polynomial
+        //~    over the domain can have, and that the evaluations of each polynomial,
+        //~    at both evaluation points, have exactly as many chunks as its commitment.
+        let chunks = evaluation_chunk_counts(index, self)?;
+        for evals in &self.evals {
+            evals.validate_chunk_lengths(&chunks)?;
+        }
+
         //~ 1. Setup the Fq-Sponge.
         let mut fq_sponge = EFqSponge::new(index.fq_sponge_params.clone());
 
+        //~ 1. Absorb the digest of the verifier index with the Fq-Sponge,
+        //~    to bind the proof to the circuit.
+        fq_sponge.absorb_fq(&[index.digest::<EFqSponge>()]);
+
+        //~ 1. Absorb the transcript prefix, if any, with the Fq-Sponge,
+        //~    preceded by its length so that different prefixes cannot lead to the same transcript.
+        if !transcript_prefix.is_empty() {
+            fq_sponge.absorb_fq(&[G::BaseField::from(transcript_prefix.len() as u64)]);
+            fq_sponge.absorb_fq(transcript_prefix);
+        }
+
         //~ 1. Absorb the commitment of the public input polynomial with the Fq-Sponge.
-        fq_sponge.absorb_g(&p_comm.unshifted);
+        fq_sponge.absorb_g_versioned(&p_comm.unshifted, index.transcript_version);
 
         //~ 1. Absorb the commitments to the registers / witness columns with the Fq-Sponge.
         self.commitments
             .w_comm
             .iter()
-            .for_each(|c| fq_sponge.absorb_g(&c.unshifted));
+            .for_each(|c| fq_sponge.absorb_g_versioned(&c.unshifted, index.transcript_version));
 
         //~ 1. If lookup is used:
         let joint_combiner = if let Some(l) = &index.lookup_index {
@@ -150,7 +184,7 @@ where
                     .runtime
                     .as_ref()
                     .ok_or(VerifyError::IncorrectRuntimeProof)?;
-                fq_sponge.absorb_g(&runtime_commit.unshifted);
+                fq_sponge.absorb_g_versioned(&runtime_commit.unshifted, index.transcript_version);
             }
 
             //~~ - If it involves queries to a multiple-column lookup table,
@@ -170,7 +204,7 @@ where
 
             //~~ - absorb the commitments to the sorted polynomials.
             for com in &lookup_commits.sorted {
-                fq_sponge.absorb_g(&com.unshifted);
+                fq_sponge.absorb_g_versioned(&com.unshifted, index.transcript_version);
             }
 
             Some(joint_combiner)
@@ -186,11 +220,11 @@ where
 
         //~ 1. If using lookup, absorb the commitment to the aggregation lookup polynomial.
         self.commitments.lookup.iter().for_each(|l| {
-            fq_sponge.absorb_g(&l.aggreg.unshifted);
+            fq_sponge.absorb_g_versioned(&l.aggreg.unshifted, index.transcript_version);
         });
 
         //~ 1. Absorb the commitment to the permutation trace with the Fq-Sponge.
-        fq_sponge.absorb_g(&self.commitments.z_comm.unshifted);
+        fq_sponge.absorb_g_versioned(&self.commitments.z_comm.unshifted, index.transcript_version);
 
         //~ 1. Sample $\alpha'$ with the Fq-Sponge.
         let alpha_chal = ScalarChallenge(fq_sponge.challenge());
@@ -199,12 +233,12 @@ where
         let alpha = alpha_chal.to_field(&index.srs().endo_r);
 
         //~ 1. Enforce that the length of the $t$ commitment is of size `PERMUTS`.
-        if self.commitments.t_comm.unshifted.len() != PERMUTS {
+        if self.commitments.t_comm.unshifted.len() != index.commitment_chunk_counts().t {
             return Err(VerifyError::IncorrectCommitmentLength("t"));
         }
 
         //~ 1. Absorb the commitment to the quotient polynomial $t$ into the argument.
-        fq_sponge.absorb_g(&self.commitments.t_comm.unshifted);
+        fq_sponge.absorb_g_versioned(&self.commitments.t_comm.unshifted, index.transcript_version);
 
         //~ 1. Sample $\zeta'$ with the Fq-Sponge.
         let zeta_chal = ScalarChallenge(fq_sponge.challenge());
@@ -227,44 +261,21 @@ where
         let mut all_alphas = index.powers_of_alpha.clone();
         all_alphas.instantiate(alpha);
 
-        // compute Lagrange base evaluation denominators
-        let w: Vec<_> = index.domain.elements().take(self.public.len()).collect();
-
-        let mut zeta_minus_x: Vec<_> = w.iter().map(|w| zeta - w).collect();
-
-        w.iter()
-            .take(self.public.len())
-            .for_each(|w| zeta_minus_x.push(zetaw - w));
-
-        ark_ff::fields::batch_inversion::<G::ScalarField>(&mut zeta_minus_x);
-
         //~ 1. Evaluate the negated public polynomial (if present) at $\zeta$ and $\zeta\omega$.
         //~
         //~    NOTE: this works only in the case when the poly segment size is not smaller than that of the domain.
         let p_eval = if !self.public.is_empty() {
             vec![
-                vec![
-                    (self
-                        .public
-                        .iter()
-                        .zip(zeta_minus_x.iter())
-                        .zip(index.domain.elements())
-                        .map(|((p, l), w)| -*l * p * w)
-                        .fold(G::ScalarField::zero(), |x, y| x + y))
-                        * (zeta1 - G::ScalarField::one())
-                        * index.domain.size_inv,
-                ],
-                vec![
-                    (self
-                        .public
-                        .iter()
-                        .zip(zeta_minus_x[self.public.len()..].iter())
-                        .zip(index.domain.elements())
-                        .map(|((p, l), w)| -*l * p * w)
-                        .fold(G::ScalarField::zero(), |x, y| x + y))
-                        * index.domain.size_inv
-                        * (zetaw.pow(&[n as u64]) - G::ScalarField::one()),
-                ],
+                vec![-generic::eval_public_input(
+                    &self.public,
+                    index.domain,
+                    zeta,
+                )],
+                vec![-generic::eval_public_input(
+                    &self.public,
+                    index.domain,
+                    zetaw,
+                )],
             ]
         } else {
             vec![Vec::<G::ScalarField>::new(), Vec::<G::ScalarField>::new()]
@@ -277,12 +288,8 @@ where
         //~~ - poseidon selector
         //~~ - the 15 register/witness
         //~~ - 6 sigmas evaluations (the last one is not evaluated)
-        for (p, e) in p_eval.iter().zip(&self.evals) {
-            fr_sponge.absorb_evaluations(p, e);
-        }
-
         //~ 1. Absorb the unique evaluation of ft: $ft(\zeta\omega)$.
-        fr_sponge.absorb(&self.ft_eval1);
+        fr_sponge.absorb_proof_scalars(&p_eval, &self.evals, &self.ft_eval1);
 
         //~ 1. Sample $v'$ with the Fr-Sponge.
         let v_chal = fr_sponge.challenge();
@@ -471,9 +478,77 @@ where
     }
 }
 
+/// Computes the number of chunks that the evaluations of each polynomial of `proof` must have,
+/// which is the number of chunks of its commitment.
+/// The commitments of the proof are checked to have no more chunks than
+/// [VerifierIndex::commitment_chunk_counts] allows.
+fn evaluation_chunk_counts<G: CommitmentCurve>(
+    index: &VerifierIndex<G>,
+    proof: &ProverProof<G>,
+) -> Result<ProofEvaluations<usize>>
+where
+    G::BaseField: PrimeField,
+{
+    let max_chunks = index.commitment_chunk_counts();
+    let chunks = |comm: &PolyComm<G>| comm.unshifted.len();
+    let proof_chunks = |comm: &PolyComm<G>, max: usize| {
+        if comm.unshifted.len() > max {
+            Err(VerifyError::TooManyChunks)
+        } else {
+            Ok(comm.unshifted.len())
+        }
+    };
+
+    let mut w = [0; COLUMNS];
+    for (w, comm) in w.iter_mut().zip(proof.commitments.w_comm.iter()) {
+        *w = proof_chunks(comm, max_chunks.w)?;
+    }
+
+    let lookup = match (&index.lookup_index, &proof.commitments.lookup) {
+        (Some(li), Some(lc)) => {
+            let sorted = lc
+                .sorted
+                .iter()
+                .map(|comm| proof_chunks(comm, max_chunks.w))
+                .collect::<Result<Vec<_>>>()?;
+            let runtime = lc
+                .runtime
+                .as_ref()
+                .map(|comm| proof_chunks(comm, max_chunks.w))
+                .transpose()?;
+            // the table commitment combines the table columns, the table ids and the runtime table
+            let table = li
+                .lookup_table
+                .iter()
+                .chain(li.table_ids.iter())
+                .chain(lc.runtime.iter())
+                .map(chunks)
+                .max()
+                .unwrap_or(0);
+            Some(LookupEvaluations {
+                sorted,
+                aggreg: proof_chunks(&lc.aggreg, max_chunks.w)?,
+                table,
+                runtime,
+            })
+        }
+        _ => None,
+    };
+
+    Ok(ProofEvaluations {
+        w,
+        z: proof_chunks(&proof.commitments.z_comm, max_chunks.z)?,
+        s: array_init(|i| chunks(&index.sigma_comm[i])),
+        lookup,
+        generic_selector: chunks(&index.generic_comm),
+        poseidon_selector: chunks(&index.psm_comm),
+    })
+}
+
 fn to_batch<'a, G, EFqSponge, EFrSponge>(
     index: &VerifierIndex<G>,
     proof: &'a ProverProof<G>,
+    transcript_prefix: &[G::BaseField],
 ) -> Result<BatchEvaluationProof<'a, G, EFqSponge>>
 where
     G: CommitmentCurve,
@@ -518,7 +593,7 @@ where
         zeta1: zeta_to_domain_size,
         ft_eval0,
         ..
-    } = proof.oracles::<EFqSponge, EFrSponge>(index, &p_comm)?;
+    } = proof.oracles::<EFqSponge, EFrSponge>(index, &p_comm, transcript_prefix)?;
 
     //~ 1. Combine the chunked polynomials' evaluations
     //~    (TODO: most likely only the quotient polynomial is chunked)
@@ -542,140 +617,103 @@ where
 
         let alphas = all_alphas.get_alphas(ArgumentType::Permutation, permutation::CONSTRAINTS);
 
-        let mut commitments = vec![&index.sigma_comm[PERMUTS - 1]];
-        let mut scalars = vec![ConstraintSystem::perm_scalars(
-            &evals,
-            oracles.beta,
-            oracles.gamma,
-            alphas,
-            zkp,
-        )];
-
-        // generic is written manually (not using the expr framework)
-        {
-            let alphas =
-                all_alphas.get_alphas(ArgumentType::Gate(GateType::Generic), generic::CONSTRAINTS);
-
-            let generic_scalars =
-                &ConstraintSystem::gnrc_scalars(alphas, &evals[0].w, evals[0].generic_selector);
-
-            let generic_com = index.coefficients_comm.iter().take(generic_scalars.len());
-
-            assert_eq!(generic_scalars.len(), generic_com.len());
-
-            scalars.extend(generic_scalars);
-            commitments.extend(generic_com);
-        }
-
-        // other gates are implemented using the expression framework
-        {
-            // TODO: Reuse constants from oracles function
-            let constants = Constants {
-                alpha: oracles.alpha,
-                beta: oracles.beta,
-                gamma: oracles.gamma,
-                joint_combiner: oracles.joint_combiner.map(|j| j.1),
-                endo_coefficient: index.endo,
-                mds: index.fr_sponge_params.mds.clone(),
-            };
-
-            for (col, tokens) in &index.linearization.index_terms {
-                let scalar =
-                    PolishToken::evaluate(tokens, index.domain, oracles.zeta, &evals, &constants)
-                        .expect("should evaluate");
+        let perm_scalar =
+            ConstraintSystem::perm_scalars(&evals, oracles.beta, oracles.gamma, alphas, zkp);
+
+        // the gates are implemented using the expression framework
+        // TODO: Reuse constants from oracles function
+        let constants = Constants {
+            alpha: oracles.alpha,
+            beta: oracles.beta,
+            gamma: oracles.gamma,
+            joint_combiner: oracles.joint_combiner.map(|j| j.1),
+            endo_coefficient: index.endo,
+            mds: index.fr_sponge_params.mds.clone(),
+        };
 
-                use Column::*;
-                match col {
-                    Witness(i) => {
-                        scalars.push(scalar);
-                        commitments.push(&proof.commitments.w_comm[*i])
-                    }
-                    Coefficient(i) => {
-                        scalars.push(scalar);
-                        commitments.push(&index.coefficients_comm[*i])
-                    }
-                    Z => {
-                        scalars.push(scalar);
-                        commitments.push(&proof.commitments.z_comm);
-                    }
-                    LookupSorted(i) => {
-                        let lookup_coms = proof
-                            .commitments
-                            .lookup
-                            .as_ref()
-                            .ok_or(VerifyError::LookupCommitmentMissing)?;
-                        scalars.push(scalar);
-                        commitments.push(&lookup_coms.sorted[*i])
+        // the commitments to the polynomials of the index terms of the linearization
+        let mut comms = HashMap::new();
+        for (col, _) in &index.linearization.index_terms {
+            use Column::*;
+            let comm = match col {
+                Witness(i) => &proof.commitments.w_comm[*i],
+                Coefficient(i) => &index.coefficients_comm[*i],
+                Z => &proof.commitments.z_comm,
+                LookupSorted(i) => {
+                    let lookup_coms = proof
+                        .commitments
+                        .lookup
+                        .as_ref()
+                        .ok_or(VerifyError::LookupCommitmentMissing)?;
+                    &lookup_coms.sorted[*i]
+                }
+                LookupAggreg => {
+                    let lookup_coms = proof
+                        .commitments
+                        .lookup
+                        .as_ref()
+                        .ok_or(VerifyError::LookupCommitmentMissing)?;
+                    &lookup_coms.aggreg
+                }
+                LookupKindIndex(i) => match index.lookup_index.as_ref() {
+                    None => {
+                        panic!("Attempted to use {:?}, but no lookup index was given", col)
                     }
-                    LookupAggreg => {
-                        let lookup_coms = proof
-                            .commitments
-                            .lookup
-                            .as_ref()
-                            .ok_or(VerifyError::LookupCommitmentMissing)?;
-                        scalars.push(scalar);
-                        commitments.push(&lookup_coms.aggreg)
+                    Some(lindex) => lindex.lookup_selectors[*i].as_ref().expect(&*format!(
+                        "Attempted to use {:?}, but it was not found in the verifier index",
+                        col
+                    )),
+                },
+                LookupTable => panic!("Lookup table is unused in the linearization"),
+                LookupRuntimeSelector => match index.lookup_index.as_ref() {
+                    None => {
+                        panic!("Attempted to use {:?}, but no lookup index was given", col)
                     }
-                    LookupKindIndex(i) => match index.lookup_index.as_ref() {
-                        None => {
-                            panic!("Attempted to use {:?}, but no lookup index was given", col)
-                        }
-                        Some(lindex) => {
-                            scalars.push(scalar);
-                            commitments.push(lindex.lookup_selectors[*i].as_ref().expect(
-                                &*format!(
-                                "Attempted to use {:?}, but it was not found in the verifier index",
-                                col
-                            ),
-                            ));
-                        }
+                    Some(lindex) => match &lindex.runtime_tables_selector {
+                        None => panic!("No runtime selector was given"),
+                        Some(comm) => comm,
                     },
-                    LookupTable => panic!("Lookup table is unused in the linearization"),
-                    LookupRuntimeSelector => match index.lookup_index.as_ref() {
-                        None => {
-                            panic!("Attempted to use {:?}, but no lookup index was given", col)
+                },
+                LookupRuntimeTable => {
+                    panic!("runtime lookup table is unused in the linearization")
+                }
+                Index(t) => {
+                    use GateType::*;
+                    match t {
+                        Zero | Generic | Lookup => {
+                            panic!("Selector for {:?} not defined", t)
                         }
-                        Some(lindex) => match &lindex.runtime_tables_selector {
-                            None => panic!("No runtime selector was given"),
-                            Some(comm) => {
-                                scalars.push(scalar);
-                                commitments.push(comm);
-                            }
-                        },
-                    },
-                    LookupRuntimeTable => {
-                        panic!("runtime lookup table is unused in the linearization")
-                    }
-                    Index(t) => {
-                        use GateType::*;
-                        let c = match t {
-                            Zero | Generic | Lookup => {
-                                panic!("Selector for {:?} not defined", t)
-                            }
-                            CompleteAdd => &index.complete_add_comm,
-                            VarBaseMul => &index.mul_comm,
-                            EndoMul => &index.emul_comm,
-                            EndoMulScalar => &index.endomul_scalar_comm,
-                            Poseidon => &index.psm_comm,
-                            ChaCha0 => &index.chacha_comm.as_ref().unwrap()[0],
-                            ChaCha1 => &index.chacha_comm.as_ref().unwrap()[1],
-                            ChaCha2 => &index.chacha_comm.as_ref().unwrap()[2],
-                            ChaChaFinal => &index.chacha_comm.as_ref().unwrap()[3],
-                            CairoClaim | CairoInstruction | CairoFlags | CairoTransition => {
-                                unimplemented!()
-                            }
-                            RangeCheck0 => &index.range_check_comm[0],
-                            RangeCheck1 => &index.range_check_comm[1],
-                        };
-                        scalars.push(scalar);
-                        commitments.push(c);
+                        CompleteAdd => &index.complete_add_comm,
+                        VarBaseMul => &index.mul_comm,
+                        EndoMul => &index.emul_comm,
+                        EndoMulScalar => &index.endomul_scalar_comm,
+                        Poseidon => &index.psm_comm,
+                        ChaCha0 => &index.chacha_comm.as_ref().unwrap()[0],
+                        ChaCha1 => &index.chacha_comm.as_ref().unwrap()[1],
+                        ChaCha2 => &index.chacha_comm.as_ref().unwrap()[2],
+                        ChaChaFinal => &index.chacha_comm.as_ref().unwrap()[3],
+                        CairoClaim | CairoInstruction | CairoFlags | CairoTransition => {
+                            unimplemented!()
+                        }
+                        RangeCheck0 => &index.range_check_comm[0],
+                        RangeCheck1 => &index.range_check_comm[1],
                     }
                 }
-            }
+            };
+            comms.insert(*col, comm);
         }
 
-        // MSM
-        PolyComm::multi_scalar_mul(&commitments, &scalars)
+        let lin_comm = linearization_commitment(
+            &index.linearization,
+            index.domain,
+            oracles.zeta,
+            &evals,
+            &constants,
+            &comms,
+        )
+        .expect("should evaluate");
+
+        &index.sigma_comm[PERMUTS - 1].scale(perm_scalar) + &lin_comm
     };
 
     //~ 1. Compute the (chuncked) commitment of $ft$
@@ -902,18 +940,62 @@ where
     EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
     EFrSponge: FrSponge<G::ScalarField>,
 {
-    let proofs = vec![(verifier_index, proof)];
-    batch_verify::<G, EFqSponge, EFrSponge>(group_map, &proofs)
+    verify_with_prefix::<G, EFqSponge, EFrSponge>(group_map, verifier_index, proof, &[])
+}
+
+/// Verify a proof [ProverProof] created with a Fiat-Shamir `transcript_prefix`
+/// (see [ProverProof::create_recursive]).
+pub fn verify_with_prefix<G, EFqSponge, EFrSponge>(
+    group_map: &G::Map,
+    verifier_index: &VerifierIndex<G>,
+    proof: &ProverProof<G>,
+    transcript_prefix: &[G::BaseField],
+) -> Result<()>
+where
+    G: CommitmentCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+{
+    let proofs = vec![(verifier_index, proof, transcript_prefix)];
+    batch_verify_with_prefixes::<G, EFqSponge, EFrSponge>(group_map, &proofs)
 }
 
 /// This function verifies the batch of zk-proofs
 ///     proofs: vector of Plonk proofs
 ///     index: VerifierIndex
 ///     RETURN: verification status
+///
+/// The challenges of each proof (including the `v` and `u` used to combine its openings)
+/// are derived from that proof's own transcript only, so the result doesn't depend on the other proofs in the batch.
+/// The opening proofs are then checked together, as a single multi-scalar multiplication
+/// using random scalars sampled by the verifier,
+/// so that a batch is accepted only if [verify](crate::verifier::verify) accepts each of its proofs.
+/// Opening the polynomials of several proofs in a single opening proof is not supported,
+/// as proofs are opened at different evaluation points.
 pub fn batch_verify<G, EFqSponge, EFrSponge>(
     group_map: &G::Map,
     proofs: &[(&VerifierIndex<G>, &ProverProof<G>)],
 ) -> Result<()>
+where
+    G: CommitmentCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+{
+    let proofs: Vec<_> = proofs
+        .iter()
+        .map(|(index, proof)| (*index, *proof, &[][..]))
+        .collect();
+    batch_verify_with_prefixes::<G, EFqSponge, EFrSponge>(group_map, &proofs)
+}
+
+/// Same as [batch_verify], for proofs created with a Fiat-Shamir transcript prefix.
+/// Each proof is given along with the prefix it was created with.
+pub fn batch_verify_with_prefixes<G, EFqSponge, EFrSponge>(
+    group_map: &G::Map,
+    proofs: &[(&VerifierIndex<G>, &ProverProof<G>, &[G::BaseField])],
+) -> Result<()>
 where
     G: CommitmentCurve,
     G::BaseField: PrimeField,
@@ -935,7 +1017,7 @@ where
     //~ 1. Ensure that all the proof's verifier index have a URS of the same length. (TODO: do they have to be the same URS though? should we check for that?)
     // TODO: Account for the different SRS lengths
     let srs = &proofs[0].0.srs();
-    for (index, _) in proofs.iter() {
+    for (index, _, _) in proofs.iter() {
         if index.srs().g.len() != srs.g.len() {
             return Err(VerifyError::DifferentSRS);
         }
@@ -948,8 +1030,12 @@ where
 
     //~ 1. Validate each proof separately following the [partial verification](#partial-verification) steps.
     let mut batch = vec![];
-    for (index, proof) in proofs {
-        batch.push(to_batch::<G, EFqSponge, EFrSponge>(index, proof)?);
+    for (index, proof, transcript_prefix) in proofs {
+        batch.push(to_batch::<G, EFqSponge, EFrSponge>(
+            index,
+            proof,
+            transcript_prefix,
+        )?);
     }
 
     //~ 1. Use the [`PolyCom.verify`](#polynomial-commitments) to verify the partially evaluated proofs.
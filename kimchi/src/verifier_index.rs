@@ -19,7 +19,7 @@ use commitment_dlog::{
     srs::SRS,
 };
 use once_cell::sync::OnceCell;
-use oracle::poseidon::ArithmeticSpongeParams;
+use oracle::{poseidon::ArithmeticSpongeParams, FqSponge, TranscriptVersion};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_with::serde_as;
 use std::io::SeekFrom::Start;
@@ -63,6 +63,10 @@ pub struct VerifierIndex<G: CommitmentCurve> {
     pub max_poly_size: usize,
     /// maximal size of the quotient polynomial according to the supported constraints
     pub max_quot_size: usize,
+    /// number of public inputs,
+    /// `None` for verifier indexes serialized before it was recorded
+    #[serde(default)]
+    pub public: Option<usize>,
     /// polynomial commitment keys
     #[serde(skip)]
     pub srs: OnceCell<Arc<SRS<G>>>,
@@ -133,9 +137,43 @@ pub struct VerifierIndex<G: CommitmentCurve> {
     pub fr_sponge_params: ArithmeticSpongeParams<G::ScalarField>,
     #[serde(skip)]
     pub fq_sponge_params: ArithmeticSpongeParams<G::BaseField>,
+
+    /// how group elements are absorbed into the Fq-Sponge, must match the prover's
+    #[serde(default)]
+    pub transcript_version: TranscriptVersion,
+
+    /// the digest of the index, computed the first time it is needed
+    /// (see [VerifierIndex::digest])
+    #[serde(skip)]
+    pub digest: OnceCell<G::BaseField>,
 }
 //~spec:endcode
 
+/// The number of chunks of the commitments to the polynomials of a proof,
+/// as computed by [VerifierIndex::commitment_chunk_counts].
+/// The commitments to the witness columns and to the permutation polynomial have at most
+/// `w` and `z` chunks (the commitment to a zero polynomial has none),
+/// the commitment to the quotient polynomial has exactly `t` chunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommitmentChunks {
+    /// number of chunks of the commitment to each witness column
+    pub w: usize,
+    /// number of chunks of the commitment to the permutation polynomial
+    pub z: usize,
+    /// number of chunks of the commitment to the quotient polynomial
+    pub t: usize,
+}
+
+/// The number of chunks of the commitment to the quotient polynomial `t`
+/// of a circuit with the given `max_quot_size` and `max_poly_size`.
+/// It does not depend on the degree of `t` in a specific proof:
+/// the prover always pads its commitment with hiding commitments to zero up to this many chunks,
+/// so that every proof for the circuit absorbs the same number of points.
+pub(crate) fn quotient_commitment_chunks(max_quot_size: usize, max_poly_size: usize) -> usize {
+    let chunks = (max_quot_size + max_poly_size - 1) / max_poly_size;
+    std::cmp::max(chunks, PERMUTS)
+}
+
 impl<'a, G: CommitmentCurve> ProverIndex<G>
 where
     G::BaseField: PrimeField,
@@ -176,6 +214,7 @@ where
             domain,
             max_poly_size: self.max_poly_size,
             max_quot_size: self.max_quot_size,
+            public: Some(self.cs.public),
             powers_of_alpha: self.powers_of_alpha.clone(),
             srs: {
                 let cell = OnceCell::new();
@@ -240,6 +279,8 @@ where
             linearization: self.linearization.clone(),
             fr_sponge_params: self.cs.fr_sponge_params.clone(),
             fq_sponge_params: self.fq_sponge_params.clone(),
+            transcript_version: self.cs.transcript_version,
+            digest: OnceCell::new(),
         }
     }
 }
@@ -248,6 +289,89 @@ impl<G: CommitmentCurve> VerifierIndex<G>
 where
     G::BaseField: PrimeField,
 {
+    /// Computes how many chunks the commitments of a proof for this circuit have,
+    /// see [CommitmentChunks].
+    pub fn commitment_chunk_counts(&self) -> CommitmentChunks {
+        let chunks = |size: usize| (size + self.max_poly_size - 1) / self.max_poly_size;
+        let n = self.domain.size();
+        CommitmentChunks {
+            w: chunks(n),
+            z: chunks(n),
+            t: quotient_commitment_chunks(self.max_quot_size, self.max_poly_size),
+        }
+    }
+
+    /// Computes the digest of the [VerifierIndex],
+    /// which binds proofs to the circuit they were created for:
+    /// the prover and the verifier absorb it first into their Fq-Sponge.
+    /// It hashes the domain size, `max_poly_size` and all the commitments
+    /// to the fixed polynomials of the circuit.
+    /// It is only computed the first time, including after deserializing the index,
+    /// and cached for the next proofs.
+    pub fn digest<EFqSponge: FqSponge<G::BaseField, G, G::ScalarField>>(&self) -> G::BaseField {
+        *self
+            .digest
+            .get_or_init(|| self.compute_digest::<EFqSponge>())
+    }
+
+    fn compute_digest<EFqSponge: FqSponge<G::BaseField, G, G::ScalarField>>(&self) -> G::BaseField {
+        let mut fq_sponge = EFqSponge::new(self.fq_sponge_params.clone());
+
+        fq_sponge.absorb_fq(&[
+            G::BaseField::from(self.domain.size),
+            G::BaseField::from(self.max_poly_size as u64),
+        ]);
+
+        for comm in self
+            .sigma_comm
+            .iter()
+            .chain(self.coefficients_comm.iter())
+            .chain([
+                &self.generic_comm,
+                &self.psm_comm,
+                &self.complete_add_comm,
+                &self.mul_comm,
+                &self.emul_comm,
+                &self.endomul_scalar_comm,
+            ])
+            .chain(self.chacha_comm.iter().flatten())
+            .chain(self.range_check_comm.iter())
+        {
+            fq_sponge.absorb_g(&comm.unshifted);
+        }
+
+        if let Some(lookup_index) = &self.lookup_index {
+            let LookupVerifierIndex {
+                lookup_used: _,
+                lookup_table,
+                lookup_selectors,
+                table_ids,
+                max_joint_size: _,
+                runtime_tables_selector,
+            } = lookup_index;
+            let LookupSelectors {
+                chacha,
+                chacha_final,
+                lookup_gate,
+                range_check_gate,
+            } = lookup_selectors;
+
+            for comm in lookup_table
+                .iter()
+                .chain(chacha)
+                .chain(chacha_final)
+                .chain(lookup_gate)
+                .chain(range_check_gate)
+                .chain(table_ids)
+                .chain(runtime_tables_selector)
+            {
+                fq_sponge.absorb_g(&comm.unshifted);
+            }
+        }
+
+        fq_sponge.challenge_fq()
+    }
+
     /// Gets srs from [VerifierIndex] lazily
     pub fn srs(&self) -> &Arc<SRS<G>> {
         self.srs.get_or_init(|| {
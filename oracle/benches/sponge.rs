@@ -0,0 +1,51 @@
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::UniformRand;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use mina_curves::pasta::{
+    fp::Fp,
+    vesta::{Affine, VestaParameters},
+};
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi, sponge::DefaultFqSponge, FqSponge, TranscriptVersion,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+
+/// Compares the time it takes to absorb the commitments of a proof
+/// with both transcript versions, for an increasing number of commitments.
+pub fn bench_absorb_g(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Absorb commitments");
+    let rng = &mut StdRng::from_seed([0u8; 32]);
+    let params = oracle::pasta::fq_kimchi::params();
+
+    for num in [16, 64, 256] {
+        let points: Vec<Affine> = (0..num)
+            .map(|_| {
+                Affine::prime_subgroup_generator()
+                    .mul(Fp::rand(rng))
+                    .into_affine()
+            })
+            .collect();
+
+        for version in [TranscriptVersion::V0, TranscriptVersion::V1] {
+            group.bench_with_input(
+                BenchmarkId::new(format!("{:?}", version), num),
+                &points,
+                |b, points| {
+                    b.iter(|| {
+                        let mut sponge = BaseSponge::new(params.clone());
+                        // commitments are absorbed one at a time in a proof
+                        for p in points {
+                            sponge.absorb_g_versioned(&[*p], version);
+                        }
+                        black_box(sponge.challenge())
+                    })
+                },
+            );
+        }
+    }
+}
+
+criterion_group!(benches, bench_absorb_g);
+criterion_main!(benches);
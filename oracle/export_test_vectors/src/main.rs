@@ -1,7 +1,7 @@
 mod vectors;
 use inner::*;
 
-/// "Usage: cargo run --all-features --bin export_test_vectors -- [hex|b10] [legacy|kimchi] <OUTPUT_FILE>",
+/// "Usage: cargo run --all-features --bin export_test_vectors -- [hex|b10] [legacy|kimchi] <OUTPUT_FILE> [INPUT_LENGTH...]",
 fn main() {
     inner::main();
 }
@@ -52,7 +52,7 @@ mod inner {
     pub(crate) fn main() {
         let args: Vec<String> = env::args().collect();
         match args.len() {
-            4 => {
+            n if n >= 4 => {
                 // parse command-line args
                 let mode: Mode = args
                     .get(1)
@@ -65,9 +65,17 @@ mod inner {
                     .parse()
                     .expect("invalid param type");
                 let output_file = args.get(3).expect("missing file");
+                let lengths: Vec<u8> = if n > 4 {
+                    args[4..]
+                        .iter()
+                        .map(|l| l.parse().expect("invalid input length"))
+                        .collect()
+                } else {
+                    vectors::DEFAULT_INPUT_LENGTHS.to_vec()
+                };
 
                 // generate vectors
-                let vectors = vectors::generate(mode, param_type);
+                let vectors = vectors::generate(mode, param_type, &lengths);
 
                 // save to output file
                 let writer: Box<dyn Write> = match output_file.as_str() {
@@ -78,7 +86,7 @@ mod inner {
             }
             _ => {
                 println!(
-                "usage: cargo run -p export_test_vectors -- [{:?}|{:?}] [legacy|kimchi] <OUTPUT_FILE>",
+                "usage: cargo run -p export_test_vectors -- [{:?}|{:?}] [legacy|kimchi] <OUTPUT_FILE> [INPUT_LENGTH...]",
                 Mode::Hex,
                 Mode::B10,
             );
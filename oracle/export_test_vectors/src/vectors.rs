@@ -53,13 +53,16 @@ fn rand_fields(rng: &mut impl Rng, length: u8) -> Vec<Fp> {
     fields
 }
 
-/// creates a set of test vectors
-pub fn generate(mode: Mode, param_type: ParamType) -> TestVectors {
+/// the input lengths used when none are given
+pub const DEFAULT_INPUT_LENGTHS: [u8; 6] = [0, 1, 2, 3, 4, 5];
+
+/// creates a set of test vectors, one for each of the input `lengths`
+pub fn generate(mode: Mode, param_type: ParamType, lengths: &[u8]) -> TestVectors {
     let mut rng = &mut rand::rngs::StdRng::from_seed([0u8; 32]);
     let mut test_vectors = vec![];
 
     // generate inputs of different lengths
-    for length in 0..6 {
+    for &length in lengths {
         // generate input & hash
         let input = rand_fields(&mut rng, length);
         let output = match param_type {
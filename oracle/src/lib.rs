@@ -5,10 +5,50 @@ pub mod poseidon;
 pub mod sponge;
 
 use ark_ff::Field;
+use serde::{Deserialize, Serialize};
+
+/// The way group elements are absorbed into an [FqSponge].
+/// It changes all the challenges derived from the sponge,
+/// so the prover and the verifier must agree on it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TranscriptVersion {
+    /// Both coordinates of each point are absorbed (see [FqSponge::absorb_g]).
+    V0,
+    /// Points are absorbed in compressed form (see [FqSponge::absorb_g_compressed]).
+    V1,
+}
+
+impl Default for TranscriptVersion {
+    fn default() -> Self {
+        TranscriptVersion::V0
+    }
+}
 
 pub trait FqSponge<Fq: Field, G, Fr> {
     fn new(p: poseidon::ArithmeticSpongeParams<Fq>) -> Self;
     fn absorb_g(&mut self, g: &[G]);
+    fn absorb_fq(&mut self, x: &[Fq]);
+
+    /// Absorbs the x-coordinates of the points.
+    /// The parities of their y-coordinates are accumulated across calls,
+    /// and absorbed packed in as few field elements as possible
+    /// before the next squeeze or absorption of another kind.
+    /// This absorbs about half as many field elements as [FqSponge::absorb_g].
+    ///
+    /// The default implementation absorbs the points with [FqSponge::absorb_g],
+    /// for sponges that do not support compressed absorption.
+    fn absorb_g_compressed(&mut self, g: &[G]) {
+        self.absorb_g(g)
+    }
+
+    /// Absorbs the points as specified by the transcript version `v`.
+    fn absorb_g_versioned(&mut self, g: &[G], v: TranscriptVersion) {
+        match v {
+            TranscriptVersion::V0 => self.absorb_g(g),
+            TranscriptVersion::V1 => self.absorb_g_compressed(g),
+        }
+    }
+
     fn absorb_fr(&mut self, x: &[Fr]);
     fn challenge(&mut self) -> Fr;
     fn challenge_fq(&mut self) -> Fq;
@@ -46,7 +46,21 @@ pub fn half_rounds<F: Field, SC: SpongeConstants>(
     params: &ArithmeticSpongeParams<F>,
     state: &mut [F],
 ) {
-    for r in 0..SC::PERM_HALF_ROUNDS_FULL {
+    half_rounds_with_rounds::<F, SC>(
+        params,
+        state,
+        SC::PERM_HALF_ROUNDS_FULL,
+        SC::PERM_ROUNDS_PARTIAL,
+    )
+}
+
+fn half_rounds_with_rounds<F: Field, SC: SpongeConstants>(
+    params: &ArithmeticSpongeParams<F>,
+    state: &mut [F],
+    half_rounds_full: usize,
+    rounds_partial: usize,
+) {
+    for r in 0..half_rounds_full {
         for (i, x) in params.round_constants[r].iter().enumerate() {
             state[i].add_assign(x);
         }
@@ -56,8 +70,8 @@ pub fn half_rounds<F: Field, SC: SpongeConstants>(
         apply_mds_matrix::<F, SC>(params, state);
     }
 
-    for r in 0..SC::PERM_ROUNDS_PARTIAL {
-        for (i, x) in params.round_constants[SC::PERM_HALF_ROUNDS_FULL + r]
+    for r in 0..rounds_partial {
+        for (i, x) in params.round_constants[half_rounds_full + r]
             .iter()
             .enumerate()
         {
@@ -67,9 +81,8 @@ pub fn half_rounds<F: Field, SC: SpongeConstants>(
         apply_mds_matrix::<F, SC>(params, state);
     }
 
-    for r in 0..SC::PERM_HALF_ROUNDS_FULL {
-        for (i, x) in params.round_constants
-            [SC::PERM_HALF_ROUNDS_FULL + SC::PERM_ROUNDS_PARTIAL + r]
+    for r in 0..half_rounds_full {
+        for (i, x) in params.round_constants[half_rounds_full + rounds_partial + r]
             .iter()
             .enumerate()
         {
@@ -82,24 +95,60 @@ pub fn half_rounds<F: Field, SC: SpongeConstants>(
     }
 }
 
+/// The number of full and partial rounds of the permutation defined by `SC`.
+pub fn rounds<SC: SpongeConstants>() -> (usize, usize) {
+    if SC::PERM_HALF_ROUNDS_FULL == 0 {
+        (SC::PERM_ROUNDS_FULL, SC::PERM_ROUNDS_PARTIAL)
+    } else {
+        (2 * SC::PERM_HALF_ROUNDS_FULL, SC::PERM_ROUNDS_PARTIAL)
+    }
+}
+
+/// The number of round constants needed by the permutation defined by `SC`
+/// with `rounds_full` full rounds and `rounds_partial` partial rounds.
+pub fn round_constants_needed<SC: SpongeConstants>(
+    rounds_full: usize,
+    rounds_partial: usize,
+) -> usize {
+    if SC::PERM_HALF_ROUNDS_FULL == 0 && SC::PERM_INITIAL_ARK {
+        rounds_full + 1
+    } else {
+        rounds_full + rounds_partial
+    }
+}
+
 pub fn poseidon_block_cipher<F: Field, SC: SpongeConstants>(
     params: &ArithmeticSpongeParams<F>,
     state: &mut Vec<F>,
+) {
+    let (rounds_full, rounds_partial) = rounds::<SC>();
+    poseidon_block_cipher_with_rounds::<F, SC>(params, state, rounds_full, rounds_partial)
+}
+
+/// Same as [poseidon_block_cipher], with `rounds_full` full rounds and `rounds_partial` partial rounds
+/// instead of the ones of `SC`. When `SC` doesn't split its full rounds in two halves,
+/// `rounds_partial` must be 0.
+pub fn poseidon_block_cipher_with_rounds<F: Field, SC: SpongeConstants>(
+    params: &ArithmeticSpongeParams<F>,
+    state: &mut Vec<F>,
+    rounds_full: usize,
+    rounds_partial: usize,
 ) {
     if SC::PERM_HALF_ROUNDS_FULL == 0 {
+        assert_eq!(rounds_partial, 0, "the permutation has no partial rounds");
         if SC::PERM_INITIAL_ARK {
             for (i, x) in params.round_constants[0].iter().enumerate() {
                 state[i].add_assign(x);
             }
-            for r in 0..SC::PERM_ROUNDS_FULL {
+            for r in 0..rounds_full {
                 full_round::<F, SC>(params, state, r + 1);
             }
         } else {
-            for r in 0..SC::PERM_ROUNDS_FULL {
+            for r in 0..rounds_full {
                 full_round::<F, SC>(params, state, r);
             }
         }
     } else {
-        half_rounds::<F, SC>(params, state);
+        half_rounds_with_rounds::<F, SC>(params, state, rounds_full / 2, rounds_partial);
     }
 }
@@ -1,8 +1,11 @@
 //! This module implements Poseidon Hash Function primitive
 
 use crate::constants::SpongeConstants;
-use crate::permutation::{full_round, poseidon_block_cipher};
+use crate::permutation::{
+    full_round, poseidon_block_cipher_with_rounds, round_constants_needed, rounds,
+};
 use ark_ff::Field;
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
@@ -32,6 +35,16 @@ pub enum SpongeState {
     Squeezed(usize),
 }
 
+/// How [ArithmeticSponge::absorb_with_padding] pads the last block of its input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaddingMode {
+    /// The last block is completed with zeros, and a full last block is left as it is.
+    Zero,
+    /// A one is appended to the input, then the last block is completed with zeros
+    /// (the `10*` padding).
+    PadOne,
+}
+
 #[serde_as]
 #[derive(Clone, Serialize, Deserialize, Default, Debug)]
 pub struct ArithmeticSpongeParams<F: Field> {
@@ -49,6 +62,10 @@ pub struct ArithmeticSponge<F: Field, SC: SpongeConstants> {
     pub state: Vec<F>,
     params: ArithmeticSpongeParams<F>,
     pub constants: std::marker::PhantomData<SC>,
+    /// whether any input was absorbed since the sponge was created or reset
+    absorbed: bool,
+    /// the number of full and partial rounds of the permutation
+    rounds: (usize, usize),
 }
 
 impl<F: Field, SC: SpongeConstants> ArithmeticSponge<F, SC> {
@@ -57,7 +74,96 @@ impl<F: Field, SC: SpongeConstants> ArithmeticSponge<F, SC> {
     }
 
     fn poseidon_block_cipher(&mut self) {
-        poseidon_block_cipher::<F, SC>(&self.params, &mut self.state);
+        let (rounds_full, rounds_partial) = self.rounds;
+        poseidon_block_cipher_with_rounds::<F, SC>(
+            &self.params,
+            &mut self.state,
+            rounds_full,
+            rounds_partial,
+        );
+    }
+
+    /// Creates a sponge whose permutation has `rounds_full` full rounds and `rounds_partial`
+    /// partial rounds, instead of the ones of `SC`.
+    /// Reducing the number of rounds makes the sponge insecure,
+    /// this is only meant to study reduced-round variants of the permutation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `params` doesn't have enough round constants for that many rounds,
+    /// or if `SC` has no partial rounds and `rounds_partial` is not 0.
+    pub fn with_rounds(
+        params: ArithmeticSpongeParams<F>,
+        rounds_full: usize,
+        rounds_partial: usize,
+    ) -> Self {
+        assert!(
+            SC::PERM_HALF_ROUNDS_FULL != 0 || rounds_partial == 0,
+            "the permutation has no partial rounds"
+        );
+        assert!(
+            SC::PERM_HALF_ROUNDS_FULL == 0 || rounds_full % 2 == 0,
+            "the full rounds of the permutation are split in two halves"
+        );
+        let needed = round_constants_needed::<SC>(rounds_full, rounds_partial);
+        assert!(
+            params.round_constants.len() >= needed,
+            "{} round constants are needed, but only {} are given",
+            needed,
+            params.round_constants.len()
+        );
+
+        let mut sponge = Self::new(params);
+        sponge.rounds = (rounds_full, rounds_partial);
+        sponge
+    }
+
+    /// Hashes each of the `inputs` independently, as if by absorbing it in a new sponge
+    /// and squeezing a single element. The inputs are hashed in parallel,
+    /// and the parameters are only cloned once per thread instead of once per input.
+    pub fn hash_many(params: &ArithmeticSpongeParams<F>, inputs: &[Vec<F>]) -> Vec<F> {
+        inputs
+            .par_iter()
+            .map_init(
+                || Self::new(params.clone()),
+                |sponge, input| {
+                    sponge.reset();
+                    sponge.absorb(input);
+                    sponge.squeeze()
+                },
+            )
+            .collect()
+    }
+
+    /// Absorbs `x` like [Sponge::absorb], then pads the last block according to `padding`,
+    /// so that the next absorbed element starts a new block.
+    pub fn absorb_with_padding(&mut self, x: &[F], padding: PaddingMode) {
+        self.absorb(x);
+        if padding == PaddingMode::PadOne {
+            self.absorb(&[F::one()]);
+        }
+        if let SpongeState::Absorbed(n) = self.sponge_state {
+            if n != 0 {
+                self.absorb(&vec![F::zero(); self.rate - n]);
+            }
+        }
+    }
+
+    /// Returns whether any input was absorbed since the sponge was created or reset.
+    pub fn has_absorbed(&self) -> bool {
+        self.absorbed
+    }
+
+    /// Same as [Sponge::squeeze], but returns `None` if nothing was absorbed yet.
+    /// Squeezing from an empty sponge is valid (for example to hash an empty input),
+    /// but in a transcript it usually means that a challenge is drawn
+    /// before the data it depends on is absorbed.
+    pub fn checked_squeeze(&mut self) -> Option<F> {
+        if self.absorbed {
+            Some(self.squeeze())
+        } else {
+            None
+        }
     }
 }
 
@@ -78,10 +184,17 @@ impl<F: Field, SC: SpongeConstants> Sponge<F, F> for ArithmeticSponge<F, SC> {
             sponge_state: SpongeState::Absorbed(0),
             params,
             constants: std::marker::PhantomData,
+            absorbed: false,
+            rounds: rounds::<SC>(),
         }
     }
 
+    /// The input is added to the state by blocks of `rate` elements,
+    /// and the state is permuted before each new block.
+    /// The last block is not padded: if it is partial, the next absorbed elements complete it,
+    /// and squeezing right away is the same as padding it with zeros ([PaddingMode::Zero]).
     fn absorb(&mut self, x: &[F]) {
+        self.absorbed |= !x.is_empty();
         for x in x.iter() {
             match self.sponge_state {
                 SpongeState::Absorbed(n) => {
@@ -125,5 +238,6 @@ impl<F: Field, SC: SpongeConstants> Sponge<F, F> for ArithmeticSponge<F, SC> {
     fn reset(&mut self) {
         self.state = vec![F::zero(); self.state.len()];
         self.sponge_state = SpongeState::Absorbed(0);
+        self.absorbed = false;
     }
 }
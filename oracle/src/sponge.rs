@@ -66,6 +66,8 @@ impl<F: PrimeField> ScalarChallenge<F> {
 pub struct DefaultFqSponge<P: SWModelParameters, SC: SpongeConstants> {
     pub sponge: ArithmeticSponge<P::BaseField, SC>,
     pub last_squeezed: Vec<u64>,
+    /// parities of the points absorbed by `absorb_g_compressed` that were not absorbed yet
+    pending_parities: Vec<bool>,
 }
 
 pub struct DefaultFrSponge<Fr: Field, SC: SpongeConstants> {
@@ -97,6 +99,36 @@ impl<Fr: PrimeField, SC: SpongeConstants> DefaultFrSponge<Fr, SC> {
             self.squeeze(num_limbs)
         }
     }
+
+    /// Squeezes a [ScalarChallenge] of [CHALLENGE_LENGTH_IN_LIMBS] limbs that is exactly uniform.
+    ///
+    /// Challenges are the low limbs of a field element squeezed from the sponge,
+    /// which are very slightly biased as the modulus `p` is not a multiple of `2^k`,
+    /// where `k = 64 * CHALLENGE_LENGTH_IN_LIMBS`.
+    /// This function squeezes again as long as the squeezed element is not below the largest multiple of `2^k`
+    /// that is smaller than `p`, so that the low limbs of the accepted element are uniform.
+    /// An element is rejected with probability less than `2^k / p` (about `2^-126` for the Pasta fields),
+    /// so the expected number of squeezes is less than `1 / (1 - 2^k / p)`.
+    /// Like [Self::squeeze], this is deterministic in the state of the sponge.
+    pub fn squeeze_uniform_scalar(&mut self) -> ScalarChallenge<Fr> {
+        self.last_squeezed = vec![];
+        let k = (64 * CHALLENGE_LENGTH_IN_LIMBS) as u32;
+        let mut modulus_high = <Fr::Params as FpParameters>::MODULUS;
+        modulus_high.divn(k);
+        loop {
+            let x = self.sponge.squeeze().into_repr();
+            let mut x_high = x;
+            x_high.divn(k);
+            // as x < p, x is below the largest multiple of 2^k smaller than p
+            // unless their high limbs are the same
+            if x_high != modulus_high {
+                return ScalarChallenge(
+                    Fr::from_repr(pack(&x.as_ref()[0..CHALLENGE_LENGTH_IN_LIMBS]))
+                        .expect("internal representation was not a valid field element"),
+                );
+            }
+        }
+    }
 }
 
 impl<P: SWModelParameters, SC: SpongeConstants> DefaultFqSponge<P, SC>
@@ -104,6 +136,22 @@ where
     P::BaseField: PrimeField,
     <P::BaseField as PrimeField>::BigInt: Into<<P::ScalarField as PrimeField>::BigInt>,
 {
+    /// Absorbs the pending parities of compressed points,
+    /// packed in as few field elements as possible.
+    fn absorb_pending_parities(&mut self) {
+        if self.pending_parities.is_empty() {
+            return;
+        }
+        let capacity = <P::BaseField as PrimeField>::Params::CAPACITY as usize;
+        let parities = std::mem::take(&mut self.pending_parities);
+        for bits in parities.chunks(capacity) {
+            self.sponge.absorb(&[P::BaseField::from_repr(
+                <P::BaseField as PrimeField>::BigInt::from_bits_le(bits),
+            )
+            .expect("packed parities fit in a field element")]);
+        }
+    }
+
     pub fn squeeze_limbs(&mut self, num_limbs: usize) -> Vec<u64> {
         if self.last_squeezed.len() >= num_limbs {
             let last_squeezed = self.last_squeezed.clone();
@@ -111,6 +159,7 @@ where
             self.last_squeezed = remaining.to_vec();
             limbs.to_vec()
         } else {
+            self.absorb_pending_parities();
             let x = self.sponge.squeeze().into_repr();
             self.last_squeezed
                 .extend(&x.as_ref()[0..HIGH_ENTROPY_LIMBS]);
@@ -120,6 +169,7 @@ where
 
     pub fn squeeze_field(&mut self) -> P::BaseField {
         self.last_squeezed = vec![];
+        self.absorb_pending_parities();
         self.sponge.squeeze()
     }
 
@@ -139,11 +189,13 @@ where
         DefaultFqSponge {
             sponge: ArithmeticSponge::new(params),
             last_squeezed: vec![],
+            pending_parities: vec![],
         }
     }
 
     fn absorb_g(&mut self, g: &[GroupAffine<P>]) {
         self.last_squeezed = vec![];
+        self.absorb_pending_parities();
         for g in g.iter() {
             if g.infinity {
                 // absorb a fake point (0, 0)
@@ -156,8 +208,29 @@ where
         }
     }
 
+    fn absorb_fq(&mut self, x: &[P::BaseField]) {
+        self.last_squeezed = vec![];
+        self.absorb_pending_parities();
+        self.sponge.absorb(x);
+    }
+
+    fn absorb_g_compressed(&mut self, g: &[GroupAffine<P>]) {
+        self.last_squeezed = vec![];
+        for g in g.iter() {
+            if g.infinity {
+                // absorb the same fake point (0, 0) as `absorb_g`
+                self.sponge.absorb(&[P::BaseField::zero()]);
+                self.pending_parities.push(false);
+            } else {
+                self.sponge.absorb(&[g.x]);
+                self.pending_parities.push(g.y.into_repr().is_odd());
+            }
+        }
+    }
+
     fn absorb_fr(&mut self, x: &[P::ScalarField]) {
         self.last_squeezed = vec![];
+        self.absorb_pending_parities();
 
         x.iter().for_each(|x| {
             let bits = x.into_repr().to_bits_le();
@@ -1,3 +1,4 @@
+use ark_ff::{One, Zero};
 use mina_curves::pasta::Fp;
 use o1_utils::FieldHelpers;
 use oracle::poseidon::Sponge as _;
@@ -6,9 +7,10 @@ use std::fs::File;
 use std::path::PathBuf; // needed for ::new() sponge
 
 use oracle::poseidon::ArithmeticSponge as Poseidon;
+use oracle::poseidon::PaddingMode;
 
-use oracle::constants::PlonkSpongeConstantsKimchi;
 use oracle::constants::PlonkSpongeConstantsLegacy;
+use oracle::constants::{PlonkSpongeConstantsKimchi, SpongeConstants};
 use oracle::pasta::fp_kimchi as SpongeParametersKimchi;
 use oracle::pasta::fp_legacy as SpongeParametersLegacy;
 
@@ -80,3 +82,112 @@ fn poseidon_test_vectors_kimchi() {
     }
     test_vectors("kimchi.json", hash);
 }
+
+#[test]
+fn poseidon_test_vectors_kimchi_zero_padding() {
+    fn hash(input: &[Fp]) -> Fp {
+        let mut hash =
+            Poseidon::<Fp, PlonkSpongeConstantsKimchi>::new(SpongeParametersKimchi::params());
+        hash.absorb_with_padding(input, PaddingMode::Zero);
+        hash.squeeze()
+    }
+    test_vectors("kimchi.json", hash);
+}
+
+#[test]
+fn poseidon_padding() {
+    let new_sponge =
+        || Poseidon::<Fp, PlonkSpongeConstantsKimchi>::new(SpongeParametersKimchi::params());
+    let hash = |input: &[Fp], padding: Option<PaddingMode>| {
+        let mut sponge = new_sponge();
+        match padding {
+            Some(padding) => sponge.absorb_with_padding(input, padding),
+            None => sponge.absorb(input),
+        }
+        sponge.squeeze()
+    };
+    let rate = PlonkSpongeConstantsKimchi::SPONGE_RATE;
+
+    for len in 0..=2 * rate {
+        let input: Vec<_> = (0..len as u64).map(|i| Fp::from(i + 1)).collect();
+
+        // the 10* padding is the same as absorbing a one after the input
+        let mut with_one = input.clone();
+        with_one.push(Fp::one());
+        assert_eq!(
+            hash(&input, Some(PaddingMode::PadOne)),
+            hash(&with_one, None)
+        );
+        assert_ne!(
+            hash(&input, Some(PaddingMode::PadOne)),
+            hash(&input, Some(PaddingMode::Zero))
+        );
+
+        // after padding, the next input starts a new block
+        let next = [Fp::from(42u32)];
+        let mut padded = new_sponge();
+        padded.absorb_with_padding(&input, PaddingMode::Zero);
+        padded.absorb(&next);
+        let mut expected = new_sponge();
+        expected.absorb(&input);
+        expected.absorb(&vec![Fp::zero(); (rate - len % rate) % rate]);
+        expected.absorb(&next);
+        assert_eq!(padded.squeeze(), expected.squeeze());
+    }
+}
+
+#[test]
+fn poseidon_hash_many() {
+    fn hash(input: &[Fp]) -> Fp {
+        let mut hash =
+            Poseidon::<Fp, PlonkSpongeConstantsKimchi>::new(SpongeParametersKimchi::params());
+        hash.absorb(input);
+        hash.squeeze()
+    }
+
+    let inputs: Vec<Vec<Fp>> = (0..20u64)
+        .map(|i| (0..i).map(|j| Fp::from(i * j + 1)).collect())
+        .collect();
+    let hashes = Poseidon::<Fp, PlonkSpongeConstantsKimchi>::hash_many(
+        &SpongeParametersKimchi::params(),
+        &inputs,
+    );
+    assert_eq!(hashes.len(), inputs.len());
+    for (input, h) in inputs.iter().zip(&hashes) {
+        assert_eq!(hash(input), *h);
+    }
+}
+
+#[test]
+fn poseidon_with_rounds() {
+    let input = [Fp::from(1u32), Fp::from(2u32), Fp::from(3u32)];
+    let hash = |mut sponge: Poseidon<Fp, PlonkSpongeConstantsKimchi>| {
+        sponge.absorb(&input);
+        sponge.squeeze()
+    };
+
+    // the standard number of rounds gives the same output as the default sponge
+    let expected = hash(Poseidon::new(SpongeParametersKimchi::params()));
+    let got = hash(Poseidon::with_rounds(
+        SpongeParametersKimchi::params(),
+        55,
+        0,
+    ));
+    assert_eq!(got, expected);
+
+    // fewer rounds give a different output
+    let reduced = hash(Poseidon::with_rounds(
+        SpongeParametersKimchi::params(),
+        5,
+        0,
+    ));
+    assert_ne!(reduced, expected);
+}
+
+#[test]
+#[should_panic(expected = "round constants are needed")]
+fn poseidon_with_too_many_rounds() {
+    let params = SpongeParametersKimchi::params();
+    let rounds = params.round_constants.len() + 1;
+    Poseidon::<Fp, PlonkSpongeConstantsKimchi>::with_rounds(params, rounds, 0);
+}
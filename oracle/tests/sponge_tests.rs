@@ -0,0 +1,131 @@
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, PrimeField, Zero};
+use mina_curves::pasta::{
+    fp::Fp,
+    vesta::{Affine, VestaParameters},
+};
+use oracle::constants::PlonkSpongeConstantsKimchi;
+use oracle::poseidon::{ArithmeticSponge, Sponge as _};
+use oracle::sponge::{DefaultFqSponge, DefaultFrSponge, CHALLENGE_LENGTH_IN_LIMBS};
+use oracle::{FqSponge, TranscriptVersion};
+
+type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+
+fn new_sponge() -> BaseSponge {
+    BaseSponge::new(oracle::pasta::fq_kimchi::params())
+}
+
+#[test]
+fn test_absorb_g_compressed() {
+    let g = Affine::prime_subgroup_generator();
+    let double = (g.into_projective() + g.into_projective()).into_affine();
+    let points = [g, -g, Affine::zero(), double];
+
+    // the compressed form is the x-coordinates followed by the packed parities
+    let mut sponge = new_sponge();
+    sponge.absorb_g_compressed(&points);
+    let mut expected = new_sponge();
+    let parities: u64 = points
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            if !p.infinity && p.y.into_repr().is_odd() {
+                1 << i
+            } else {
+                0
+            }
+        })
+        .sum();
+    for p in &points {
+        expected.sponge.absorb(&[p.x]);
+    }
+    expected.sponge.absorb(&[parities.into()]);
+    assert_eq!(sponge.challenge(), expected.challenge());
+
+    // the parities are packed across calls, until the next challenge
+    let mut split = new_sponge();
+    split.absorb_g_compressed(&points[..1]);
+    split.absorb_g_compressed(&points[1..]);
+    let mut whole = new_sponge();
+    whole.absorb_g_compressed(&points);
+    assert_eq!(split.challenge(), whole.challenge());
+
+    // a point and its negation only differ in the parity
+    let mut pos = new_sponge();
+    pos.absorb_g_compressed(&[g]);
+    let mut neg = new_sponge();
+    neg.absorb_g_compressed(&[-g]);
+    assert_ne!(pos.challenge(), neg.challenge());
+}
+
+#[test]
+fn test_absorb_g_versioned() {
+    let g = Affine::prime_subgroup_generator();
+
+    let mut v0 = new_sponge();
+    v0.absorb_g_versioned(&[g], TranscriptVersion::V0);
+    let mut uncompressed = new_sponge();
+    uncompressed.absorb_g(&[g]);
+    assert_eq!(v0.challenge(), uncompressed.challenge());
+
+    let mut v1 = new_sponge();
+    v1.absorb_g_versioned(&[g], TranscriptVersion::V1);
+    let mut compressed = new_sponge();
+    compressed.absorb_g_compressed(&[g]);
+    assert_eq!(v1.challenge(), compressed.challenge());
+}
+
+#[test]
+fn test_checked_squeeze() {
+    use mina_curves::pasta::fq::Fq;
+    use oracle::poseidon::ArithmeticSponge;
+
+    let mut sponge =
+        ArithmeticSponge::<Fq, PlonkSpongeConstantsKimchi>::new(oracle::pasta::fq_kimchi::params());
+    assert!(!sponge.has_absorbed());
+    assert_eq!(sponge.checked_squeeze(), None);
+
+    sponge.absorb(&[Fq::zero()]);
+    assert!(sponge.has_absorbed());
+    let x = sponge.checked_squeeze().unwrap();
+    sponge.reset();
+    sponge.absorb(&[Fq::zero()]);
+    assert_eq!(sponge.squeeze(), x);
+
+    sponge.reset();
+    assert_eq!(sponge.checked_squeeze(), None);
+}
+
+#[test]
+fn test_squeeze_uniform_scalar() {
+    let new_fr_sponge = || {
+        let mut sponge = DefaultFrSponge::<Fp, PlonkSpongeConstantsKimchi> {
+            sponge: ArithmeticSponge::new(oracle::pasta::fp_kimchi::params()),
+            last_squeezed: vec![],
+        };
+        sponge.sponge.absorb(&[Fp::from(42u64)]);
+        sponge
+    };
+
+    // the challenge only depends on the state of the sponge
+    let (mut s1, mut s2) = (new_fr_sponge(), new_fr_sponge());
+    for _ in 0..10 {
+        let c1 = s1.squeeze_uniform_scalar().0;
+        assert_eq!(c1, s2.squeeze_uniform_scalar().0);
+
+        // it has the length of a regular challenge
+        let limbs = c1.into_repr();
+        assert!(limbs.as_ref()[CHALLENGE_LENGTH_IN_LIMBS..]
+            .iter()
+            .all(|&l| l == 0));
+    }
+
+    // an element is rejected with negligible probability,
+    // so in practice the challenge is the same as the regular one
+    let mut regular = new_fr_sponge();
+    let mut uniform = new_fr_sponge();
+    assert_eq!(
+        uniform.squeeze_uniform_scalar().0,
+        regular.squeeze(CHALLENGE_LENGTH_IN_LIMBS)
+    );
+}
@@ -2,7 +2,9 @@ use ark_ec::ProjectiveCurve;
 use ark_ff::{Field, Zero};
 
 use crate::commitment::CommitmentCurve;
+use crate::srs::SRS;
 use crate::PolyComm;
+use o1_utils::chunked_polynomial::ChunkedPolynomial;
 
 impl<C> PolyComm<C>
 where
@@ -12,19 +14,25 @@ where
     /// Note that it ignores the shifted part.
     // TODO(mimoo): better name for this function
     pub fn chunk_commitment(&self, zeta_n: C::ScalarField) -> Self {
+        PolyComm {
+            unshifted: vec![self.combine_chunks(zeta_n)],
+            shifted: self.shifted,
+        }
+    }
+
+    /// Collapses the unshifted chunks into a single group element
+    /// `chunk[0] + pt_pow chunk[1] + pt_pow^2 chunk[2] + ...`.
+    /// Note that it ignores the shifted part.
+    pub fn combine_chunks(&self, pt_pow: C::ScalarField) -> C {
         let mut res = C::Projective::zero();
         // use Horner's to compute chunk[0] + z^n chunk[1] + z^2n chunk[2] + ...
         // as ( chunk[-1] * z^n + chunk[-2] ) * z^n + chunk[-3]
         // (https://en.wikipedia.org/wiki/Horner%27s_method)
         for chunk in self.unshifted.iter().rev() {
-            res *= zeta_n;
+            res *= pt_pow;
             res.add_assign_mixed(chunk);
         }
-
-        PolyComm {
-            unshifted: vec![res.into_affine()],
-            shifted: self.shifted,
-        }
+        res.into_affine()
     }
 }
 
@@ -47,3 +55,82 @@ where
         res
     }
 }
+
+impl<G: CommitmentCurve> SRS<G> {
+    /// Commits to each chunk of a chunked polynomial, without hiding.
+    /// The result has one unshifted commitment per chunk (a zero chunk is committed to the identity),
+    /// so that it is the same multi-chunk commitment as [SRS::commit_non_hiding]
+    /// on the dense polynomial.
+    pub fn commit_chunked(&self, chunked: &ChunkedPolynomial<G::ScalarField>) -> PolyComm<G> {
+        assert!(
+            chunked.size <= self.g.len(),
+            "chunks of size {} do not fit in an SRS of size {}",
+            chunked.size,
+            self.g.len()
+        );
+        let unshifted = chunked
+            .polys
+            .iter()
+            .map(|poly| {
+                let comm = self.commit_non_hiding(poly, None);
+                comm.unshifted.first().copied().unwrap_or_else(G::zero)
+            })
+            .collect();
+        PolyComm {
+            unshifted,
+            shifted: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::srs::SRS;
+    use ark_ec::{AffineCurve, ProjectiveCurve};
+    use ark_ff::UniformRand;
+    use ark_poly::{univariate::DensePolynomial, UVPolynomial};
+    use mina_curves::pasta::{fp::Fp, vesta::Affine as VestaG};
+    use o1_utils::ExtendedDensePolynomial;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_combine_chunks() {
+        let n = 8;
+        let srs = SRS::<VestaG>::create(n);
+        let rng = &mut StdRng::from_seed([0u8; 32]);
+
+        // a polynomial of degree 2n - 1 is committed in two chunks
+        let poly = DensePolynomial::<Fp>::rand(2 * n - 1, rng);
+        let comm = srs.commit_non_hiding(&poly, None);
+        assert_eq!(comm.unshifted.len(), 2);
+
+        // by linearity, combining the chunks with zeta^n is the same as
+        // committing to the chunks combined with zeta^n
+        let zeta_n = Fp::rand(rng);
+        let expected =
+            srs.commit_non_hiding(&poly.to_chunked_polynomial(n).linearize(zeta_n), None);
+        assert_eq!(expected.unshifted.len(), 1);
+        assert_eq!(comm.combine_chunks(zeta_n), expected.unshifted[0]);
+        assert_eq!(
+            comm.combine_chunks(zeta_n),
+            comm.unshifted[0] + comm.unshifted[1].mul(zeta_n).into_affine()
+        );
+    }
+
+    #[test]
+    fn test_commit_chunked() {
+        let n = 8;
+        let srs = SRS::<VestaG>::create(n);
+        let rng = &mut StdRng::from_seed([0u8; 32]);
+
+        let poly = DensePolynomial::<Fp>::rand(3 * n - 1, rng);
+        let chunked = poly.to_chunked_polynomial(n);
+        let comm = srs.commit_chunked(&chunked);
+        assert_eq!(comm.unshifted, srs.commit_non_hiding(&poly, None).unshifted);
+
+        // the chunks combine to the commitment of the linearized polynomial
+        let zeta_n = Fp::rand(rng);
+        let expected = srs.commit_non_hiding(&chunked.linearize(zeta_n), None);
+        assert_eq!(comm.combine_chunks(zeta_n), expected.unshifted[0]);
+    }
+}
@@ -18,7 +18,7 @@ use ark_poly::{
     univariate::DensePolynomial, EvaluationDomain, Evaluations, Radix2EvaluationDomain as D,
 };
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use core::ops::{Add, Sub};
+use core::ops::{Add, Mul, Sub};
 use groupmap::{BWParameters, GroupMap};
 use o1_utils::math;
 use o1_utils::ExtendedDensePolynomial as _;
@@ -213,6 +213,16 @@ impl<'a, 'b, C: AffineCurve> Sub<&'a PolyComm<C>> for &'b PolyComm<C> {
     }
 }
 
+impl<'a, C: AffineCurve> Mul<C::ScalarField> for &'a PolyComm<C> {
+    type Output = PolyComm<C>;
+
+    /// Multiplies each chunk of the commitment (and the shifted part) by `elm`,
+    /// see [PolyComm::scale].
+    fn mul(self, elm: C::ScalarField) -> PolyComm<C> {
+        self.scale(elm)
+    }
+}
+
 impl<C: AffineCurve> PolyComm<C> {
     pub fn scale(&self, c: C::ScalarField) -> PolyComm<C> {
         PolyComm {
@@ -660,6 +670,20 @@ impl<G: CommitmentCurve> SRS<G> {
         }
     }
 
+    /// Same as [SRS::commit_evaluations_non_hiding] for several polynomials,
+    /// whose multi-scalar multiplications are computed in parallel.
+    pub fn commit_evaluations_non_hiding_batch(
+        &self,
+        domain: D<G::ScalarField>,
+        plnms: &[Evaluations<G::ScalarField, D<G::ScalarField>>],
+        max: Option<usize>,
+    ) -> Vec<PolyComm<G>> {
+        plnms
+            .par_iter()
+            .map(|plnm| self.commit_evaluations_non_hiding(domain, plnm, max))
+            .collect()
+    }
+
     pub fn commit_evaluations(
         &self,
         domain: D<G::ScalarField>,
@@ -1028,6 +1052,72 @@ mod tests {
 
         assert!(srs.verify(&group_map, &mut batch, rng));
     }
+
+    #[test]
+    fn test_opening_proof_challenges() {
+        let coeffs: [Fp; 10] = array_init(|i| Fp::from(i as u32));
+        let poly = DensePolynomial::<Fp>::from_coefficients_slice(&coeffs);
+
+        let srs = SRS::<VestaG>::create(16);
+        let rng = &mut StdRng::from_seed([0u8; 32]);
+        let commitment = srs.commit(&poly, None, rng);
+
+        let (u, v) = (Fp::rand(rng), Fp::rand(rng));
+        let group_map = <VestaG as CommitmentCurve>::Map::setup();
+        let sponge = DefaultFqSponge::<_, SC>::new(spongeFqParams());
+
+        let elm = vec![Fp::rand(rng), Fp::rand(rng)];
+        let polys = vec![(&poly, None, commitment.blinders)];
+        let opening_proof = srs.open(&group_map, &polys, &elm, v, u, sponge.clone(), rng);
+
+        // replay the sponge of `open` up to the rounds of the inner-product argument
+        let evals = elm
+            .iter()
+            .map(|e| poly.to_chunked_polynomial(srs.g.len()).evaluate_chunks(*e))
+            .collect();
+        let combined_inner_product =
+            combined_inner_product::<VestaG>(&elm, &v, &u, &[(evals, None)], srs.g.len());
+        let mut sponge = sponge;
+        sponge.absorb_fr(&[shift_scalar::<VestaG>(combined_inner_product)]);
+        let _t = sponge.challenge_fq();
+
+        let Challenges { chal, chal_inv } = opening_proof.challenges(&srs.endo_r, &mut sponge);
+        assert_eq!(chal.len(), math::ceil_log2(srs.g.len()));
+        for (c, c_inv) in chal.iter().zip(&chal_inv) {
+            assert_eq!(*c * c_inv, Fp::one());
+        }
+
+        // the challenges used by `open` fold the generators into `sg`
+        let b = DensePolynomial::from_coefficients_vec(b_poly_coefficients(&chal));
+        assert_eq!(
+            srs.commit_non_hiding(&b, None).unshifted,
+            vec![opening_proof.sg]
+        );
+    }
+
+    #[test]
+    fn test_commitment_linear_combination() {
+        let n = 8;
+        let srs = SRS::<VestaG>::create(n);
+        let rng = &mut StdRng::from_seed([0u8; 32]);
+
+        // polynomials of different numbers of chunks, with a degree bound
+        let poly1 = DensePolynomial::<Fp>::rand(2 * n - 1, rng);
+        let poly2 = DensePolynomial::<Fp>::rand(n / 2, rng);
+        let max = Some(2 * n - 1);
+        let comm1 = srs.commit_non_hiding(&poly1, max);
+        let comm2 = srs.commit_non_hiding(&poly2, max);
+
+        // commitments are linear in the polynomials
+        let scalar = Fp::rand(rng);
+        let combined = &(&comm1 * scalar) + &comm2;
+        let scaled1 = DensePolynomial::from_coefficients_vec(
+            poly1.coeffs.iter().map(|c| *c * scalar).collect(),
+        );
+        let expected = srs.commit_non_hiding(&(&scaled1 + &poly2), max);
+        assert_eq!(combined.unshifted, expected.unshifted);
+        assert_eq!(combined.shifted, expected.shifted);
+    }
 }
 
 //
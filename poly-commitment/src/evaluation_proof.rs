@@ -331,6 +331,10 @@ pub struct Challenges<F> {
 }
 
 impl<G: AffineCurve> OpeningProof<G> {
+    /// Re-derives the challenges of the rounds of the inner-product argument,
+    /// before they are mapped to the scalar field with the endomorphism.
+    /// `sponge` must be in the state of the sponge given to [SRS::open]
+    /// once the combined inner product is absorbed.
     pub fn prechallenges<EFqSponge: FqSponge<G::BaseField, G, G::ScalarField>>(
         &self,
         sponge: &mut EFqSponge,
@@ -346,6 +350,11 @@ impl<G: AffineCurve> OpeningProof<G> {
             .collect()
     }
 
+    /// Re-derives the challenges of the rounds of the inner-product argument, and their inverses.
+    /// `sponge` must be in the state of the sponge given to [SRS::open]
+    /// once the combined inner product is absorbed and the challenge `t` is squeezed.
+    /// A recursive proof can fold the challenges as a [b_poly] whose commitment is `sg`
+    /// (see [b_poly_coefficients]).
     pub fn challenges<EFqSponge: FqSponge<G::BaseField, G, G::ScalarField>>(
         &self,
         endo_r: &G::ScalarField,
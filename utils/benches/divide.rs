@@ -0,0 +1,32 @@
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, Radix2EvaluationDomain, UVPolynomial,
+};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use mina_curves::pasta::fp::Fp;
+use o1_utils::ExtendedDensePolynomial as _;
+use rand::{rngs::StdRng, SeedableRng};
+
+/// Compares the division of a polynomial of the degree of the prover's quotient
+/// (`8n` coefficients) by the vanishing polynomial `X^n - 1`,
+/// with arkworks and with `divide_by_x_n_minus_1`.
+pub fn bench_divide(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Divide by vanishing polynomial");
+    let rng = &mut StdRng::from_seed([0u8; 32]);
+
+    for log_size in [10, 14, 16] {
+        let domain = Radix2EvaluationDomain::<Fp>::new(1 << log_size).unwrap();
+        let f = DensePolynomial::<Fp>::rand(8 * domain.size() - 1, rng);
+
+        group.bench_with_input(BenchmarkId::new("arkworks", domain.size()), &f, |b, f| {
+            b.iter(|| black_box(f.divide_by_vanishing_poly(domain).unwrap()))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("divide_by_x_n_minus_1", domain.size()),
+            &f,
+            |b, f| b.iter(|| black_box(f.divide_by_x_n_minus_1(domain.size()))),
+        );
+    }
+}
+
+criterion_group!(benches, bench_divide);
+criterion_main!(benches);
@@ -0,0 +1,44 @@
+use ark_ff::UniformRand;
+use ark_poly::{EvaluationDomain, Evaluations, Radix2EvaluationDomain};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use mina_curves::pasta::fp::Fp;
+use o1_utils::evaluations::interpolate_batch;
+use rand::{rngs::StdRng, SeedableRng};
+
+/// Number of polynomials interpolated together, as many as there are witness columns.
+const BATCH_SIZE: usize = 15;
+
+/// Compares interpolating a batch of evaluations one after the other and with [interpolate_batch].
+pub fn bench_interpolate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Interpolate");
+    let rng = &mut StdRng::from_seed([0u8; 32]);
+
+    for log_size in [10, 14, 16] {
+        let domain = Radix2EvaluationDomain::<Fp>::new(1 << log_size).unwrap();
+        let evals: Vec<_> = (0..BATCH_SIZE)
+            .map(|_| {
+                let values = (0..domain.size()).map(|_| Fp::rand(rng)).collect();
+                Evaluations::from_vec_and_domain(values, domain)
+            })
+            .collect();
+
+        group.bench_with_input(
+            BenchmarkId::new("one by one", domain.size()),
+            &evals,
+            |b, evals| {
+                b.iter(|| {
+                    let polys: Vec<_> = evals.iter().map(|e| e.clone().interpolate()).collect();
+                    black_box(polys)
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("batch", domain.size()),
+            &evals,
+            |b, evals| b.iter(|| black_box(interpolate_batch(evals.clone()))),
+        );
+    }
+}
+
+criterion_group!(benches, bench_interpolate);
+criterion_main!(benches);
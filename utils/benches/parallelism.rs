@@ -0,0 +1,36 @@
+use ark_ff::UniformRand;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use mina_curves::pasta::fp::Fp;
+use o1_utils::parallelism::for_each_mut_with_threshold;
+use rand::{rngs::StdRng, SeedableRng};
+
+/// Compares a serial and a parallel scaling of vectors of increasing size,
+/// to find the size at which rayon starts paying off (see `DEFAULT_RAYON_THRESHOLD`).
+pub fn bench_scale(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Scale");
+    let rng = &mut StdRng::from_seed([0u8; 32]);
+    let elm = Fp::rand(rng);
+
+    for log_size in 4..=16 {
+        let size = 1usize << log_size;
+        let coeffs: Vec<Fp> = (0..size).map(|_| Fp::rand(rng)).collect();
+
+        group.bench_with_input(BenchmarkId::new("serial", size), &coeffs, |b, coeffs| {
+            b.iter(|| {
+                let mut coeffs = coeffs.clone();
+                for_each_mut_with_threshold(&mut coeffs, usize::MAX, |c| *c *= &elm);
+                black_box(coeffs)
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("parallel", size), &coeffs, |b, coeffs| {
+            b.iter(|| {
+                let mut coeffs = coeffs.clone();
+                for_each_mut_with_threshold(&mut coeffs, 0, |c| *c *= &elm);
+                black_box(coeffs)
+            })
+        });
+    }
+}
+
+criterion_group!(benches, bench_scale);
+criterion_main!(benches);
@@ -1,11 +1,10 @@
 //! This adds a few utility functions for the [DensePolynomial] arkworks type.
 
+use crate::{chunked_polynomial::ChunkedPolynomial, parallelism};
 use ark_ff::Field;
 use ark_poly::{univariate::DensePolynomial, UVPolynomial};
 use rayon::prelude::*;
 
-use crate::chunked_polynomial::ChunkedPolynomial;
-
 //
 // ExtendedDensePolynomial trait
 //
@@ -23,15 +22,25 @@ pub trait ExtendedDensePolynomial<F: Field> {
 
     /// Convert a polynomial into chunks.
     fn to_chunked_polynomial(&self, size: usize) -> ChunkedPolynomial<F>;
+
+    /// Evaluates the polynomial at `point` by splitting it into chunks of `max_poly_size`
+    /// coefficients, evaluating them, and combining the evaluations with powers of
+    /// `point^max_poly_size`.
+    fn eval_combined(&self, point: F, max_poly_size: usize) -> F;
+
+    /// Divides the polynomial by `X^n - 1`, the vanishing polynomial of a multiplicative
+    /// subgroup of size `n`, and returns the quotient and the remainder.
+    /// This is the same as `divide_by_vanishing_poly` for such a domain,
+    /// but only takes additions.
+    fn divide_by_x_n_minus_1(&self, n: usize) -> (Self, Self)
+    where
+        Self: Sized;
 }
 
 impl<F: Field> ExtendedDensePolynomial<F> for DensePolynomial<F> {
     fn scale(&self, elm: F) -> Self {
         let mut result = self.clone();
-        result
-            .coeffs
-            .par_iter_mut()
-            .for_each(|coeff| *coeff *= &elm);
+        parallelism::for_each_mut(&mut result.coeffs, |coeff| *coeff *= &elm);
         result
     }
 
@@ -62,6 +71,44 @@ impl<F: Field> ExtendedDensePolynomial<F> for DensePolynomial<F> {
             size: chunk_size,
         }
     }
+
+    fn eval_combined(&self, point: F, max_poly_size: usize) -> F {
+        let chunks = self
+            .to_chunked_polynomial(max_poly_size)
+            .evaluate_chunks(point);
+        Self::eval_polynomial(&chunks, point.pow(&[max_poly_size as u64]))
+    }
+
+    fn divide_by_x_n_minus_1(&self, n: usize) -> (Self, Self) {
+        assert!(n > 0, "cannot divide by X^0 - 1");
+        if self.coeffs.len() <= n {
+            return (DensePolynomial::from_coefficients_vec(vec![]), self.clone());
+        }
+
+        // p = q * (X^n - 1) + r gives q_i = p_{i + n} + q_{i + n},
+        // so q is computed block by block of n coefficients, from the highest one
+        let mut quotient = self.coeffs[n..].to_vec();
+        let blocks = (quotient.len() + n - 1) / n;
+        for k in (0..blocks - 1).rev() {
+            let (lo, hi) = quotient.split_at_mut((k + 1) * n);
+            lo[k * n..]
+                .par_iter_mut()
+                .zip(&hi[..std::cmp::min(n, hi.len())])
+                .for_each(|(q, q_next)| *q += q_next);
+        }
+
+        // and r_i = p_i + q_i
+        let mut remainder = self.coeffs[..n].to_vec();
+        remainder
+            .par_iter_mut()
+            .zip(&quotient)
+            .for_each(|(r, q)| *r += q);
+
+        (
+            DensePolynomial::from_coefficients_vec(quotient),
+            DensePolynomial::from_coefficients_vec(remainder),
+        )
+    }
 }
 
 //
@@ -71,9 +118,60 @@ impl<F: Field> ExtendedDensePolynomial<F> for DensePolynomial<F> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ark_ff::One;
-    use ark_poly::{univariate::DensePolynomial, UVPolynomial};
+    use ark_ff::{One, UniformRand, Zero};
+    use ark_poly::{
+        univariate::DensePolynomial, EvaluationDomain, Polynomial, Radix2EvaluationDomain,
+        UVPolynomial,
+    };
     use mina_curves::pasta::fp::Fp;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_scale() {
+        let one = Fp::one();
+        let two = one + one;
+        let three = two + one;
+
+        // small polynomials are scaled serially, large ones in parallel
+        for size in [4, 2 * parallelism::rayon_threshold()] {
+            let f = DensePolynomial::from_coefficients_vec(vec![two; size]);
+            let scaled = f.scale(three);
+            assert!(scaled.coeffs.iter().all(|c| *c == two * three));
+        }
+    }
+
+    #[test]
+    fn test_eval_combined() {
+        let rng = &mut StdRng::from_seed([0u8; 32]);
+        let max_poly_size = 8;
+        let point = Fp::rand(rng);
+
+        for degree in [0, max_poly_size - 1, max_poly_size, 3 * max_poly_size + 2] {
+            let f = DensePolynomial::<Fp>::rand(degree, rng);
+            assert_eq!(f.eval_combined(point, max_poly_size), f.evaluate(&point));
+        }
+    }
+
+    #[test]
+    fn test_divide_by_x_n_minus_1() {
+        let rng = &mut StdRng::from_seed([0u8; 32]);
+        let n = 16;
+        let domain = Radix2EvaluationDomain::<Fp>::new(n).unwrap();
+
+        for degree in [0, n - 1, n, n + 1, 3 * n - 1, 8 * n - 1, 8 * n + 3] {
+            let f = DensePolynomial::<Fp>::rand(degree, rng);
+            let expected = f.divide_by_vanishing_poly(domain).unwrap();
+            assert_eq!(f.divide_by_x_n_minus_1(n), expected);
+        }
+
+        // multiples of the vanishing polynomial have no remainder
+        let q = DensePolynomial::<Fp>::rand(5 * n, rng);
+        let vanishing: DensePolynomial<Fp> = domain.vanishing_polynomial().into();
+        let f = &q * &vanishing;
+        let (quotient, remainder) = f.divide_by_x_n_minus_1(n);
+        assert_eq!(quotient, q);
+        assert!(remainder.is_zero());
+    }
 
     #[test]
     fn test_chunk() {
@@ -1,9 +1,14 @@
 //! This adds a few utility functions for the [Evaluations] arkworks type.
 
 use ark_ff::FftField;
-use ark_poly::{Evaluations, Radix2EvaluationDomain};
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, Evaluations, Radix2EvaluationDomain,
+    UVPolynomial,
+};
 use rayon::prelude::*;
 
+use crate::parallelism;
+
 /// An extension for the [Evaluations] type.
 pub trait ExtendedEvaluations<F: FftField> {
     /// This function "scales" (multiplies) a polynomial with a scalar
@@ -23,13 +28,13 @@ pub trait ExtendedEvaluations<F: FftField> {
 impl<F: FftField> ExtendedEvaluations<F> for Evaluations<F, Radix2EvaluationDomain<F>> {
     fn scale(&self, elm: F) -> Self {
         let mut result = self.clone();
-        result.evals.par_iter_mut().for_each(|coeff| *coeff *= &elm);
+        parallelism::for_each_mut(&mut result.evals, |coeff| *coeff *= &elm);
         result
     }
 
     fn square(&self) -> Self {
         let mut result = self.clone();
-        result.evals.par_iter_mut().for_each(|e| {
+        parallelism::for_each_mut(&mut result.evals, |e| {
             let _ = e.square_in_place();
         });
         result
@@ -37,10 +42,7 @@ impl<F: FftField> ExtendedEvaluations<F> for Evaluations<F, Radix2EvaluationDoma
 
     fn pow(&self, pow: usize) -> Self {
         let mut result = self.clone();
-        result
-            .evals
-            .par_iter_mut()
-            .for_each(|e| *e = e.pow([pow as u64]));
+        parallelism::for_each_mut(&mut result.evals, |e| *e = e.pow([pow as u64]));
         result
     }
 
@@ -54,3 +56,64 @@ impl<F: FftField> ExtendedEvaluations<F> for Evaluations<F, Radix2EvaluationDoma
         result
     }
 }
+
+/// Interpolates several evaluations over the same domain at once.
+/// The inverse FFTs are performed in place, in parallel,
+/// which is faster than interpolating each of the evaluations in turn
+/// when they are too small for a single FFT to use all the threads.
+///
+/// # Panics
+///
+/// Panics if the evaluations are not all over the same domain.
+pub fn interpolate_batch<F: FftField>(
+    evals: Vec<Evaluations<F, Radix2EvaluationDomain<F>>>,
+) -> Vec<DensePolynomial<F>> {
+    let domain = match evals.first() {
+        None => return vec![],
+        Some(e) => e.domain(),
+    };
+    assert!(
+        evals.iter().all(|e| e.domain() == domain),
+        "all the evaluations should be over the same domain"
+    );
+
+    evals
+        .into_par_iter()
+        .map(|e| {
+            let mut coeffs = e.evals;
+            domain.ifft_in_place(&mut coeffs);
+            DensePolynomial::from_coefficients_vec(coeffs)
+        })
+        .collect()
+}
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_poly::Polynomial;
+    use mina_curves::pasta::fp::Fp;
+
+    #[test]
+    fn test_interpolate_batch() {
+        let domain = Radix2EvaluationDomain::<Fp>::new(8).unwrap();
+        let evals: Vec<_> = (0..3u64)
+            .map(|i| {
+                let values = (0..8).map(|j| Fp::from(i * 8 + j)).collect();
+                Evaluations::from_vec_and_domain(values, domain)
+            })
+            .collect();
+
+        let polys = interpolate_batch(evals.clone());
+        assert_eq!(polys.len(), evals.len());
+        for (p, e) in polys.iter().zip(evals) {
+            assert_eq!(*p, e.clone().interpolate());
+            for (x, y) in domain.elements().zip(&e.evals) {
+                assert_eq!(p.evaluate(&x), *y);
+            }
+        }
+    }
+}
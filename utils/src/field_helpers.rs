@@ -13,6 +13,15 @@ pub enum FieldHelpersError {
 }
 pub type Result<T> = std::result::Result<T, FieldHelpersError>;
 
+/// The byte order of a serialized field element
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    /// Least significant byte first, as used by arkworks serialization
+    Little,
+    /// Most significant byte first
+    Big,
+}
+
 /// Field element helpers
 ///   Unless otherwise stated everything is in little-endian byte order.
 pub trait FieldHelpers<F> {
@@ -22,15 +31,21 @@ pub trait FieldHelpers<F> {
     /// Deserialize from little-endian hex
     fn from_hex(hex: &str) -> Result<F>;
 
+    /// Deserialize from hex in the given byte order
+    fn from_hex_with_endianness(hex: &str, endianness: Endianness) -> Result<F>;
+
     /// Deserialize from bits
     fn from_bits(bits: &[bool]) -> Result<F>;
 
     /// Serialize to bytes
     fn to_bytes(self) -> Vec<u8>;
 
-    /// Serialize to hex
+    /// Serialize to little-endian hex
     fn to_hex(self) -> String;
 
+    /// Serialize to hex in the given byte order
+    fn to_hex_with_endianness(self, endianness: Endianness) -> String;
+
     /// Serialize to bits
     fn to_bits(self) -> Vec<bool>;
 
@@ -61,6 +76,14 @@ impl<F: Field> FieldHelpers<F> for F {
         F::deserialize(&mut &bytes[..]).map_err(|_| FieldHelpersError::DeserializeBytes)
     }
 
+    fn from_hex_with_endianness(hex: &str, endianness: Endianness) -> Result<F> {
+        let mut bytes: Vec<u8> = hex::decode(hex).map_err(|_| FieldHelpersError::DecodeHex)?;
+        if endianness == Endianness::Big {
+            bytes.reverse();
+        }
+        F::deserialize(&mut &bytes[..]).map_err(|_| FieldHelpersError::DeserializeBytes)
+    }
+
     fn from_bits(bits: &[bool]) -> Result<F> {
         let bytes = bits
             .iter()
@@ -85,6 +108,14 @@ impl<F: Field> FieldHelpers<F> for F {
         hex::encode(self.to_bytes())
     }
 
+    fn to_hex_with_endianness(self, endianness: Endianness) -> String {
+        let mut bytes = self.to_bytes();
+        if endianness == Endianness::Big {
+            bytes.reverse();
+        }
+        hex::encode(bytes)
+    }
+
     fn to_bits(self) -> Vec<bool> {
         self.to_bytes().iter().fold(vec![], |mut bits, byte| {
             let mut byte = *byte;
@@ -158,6 +189,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn field_hex_endianness() {
+        let one_le = "0100000000000000000000000000000000000000000000000000000000000000";
+        let one_be = "0000000000000000000000000000000000000000000000000000000000000001";
+        assert_eq!(BaseField::from_hex(one_le), Ok(BaseField::one()));
+        assert_eq!(
+            BaseField::from_hex_with_endianness(one_le, Endianness::Little),
+            Ok(BaseField::one())
+        );
+        assert_eq!(
+            BaseField::from_hex_with_endianness(one_be, Endianness::Big),
+            Ok(BaseField::one())
+        );
+        assert_eq!(
+            BaseField::one().to_hex_with_endianness(Endianness::Big),
+            one_be
+        );
+
+        let fe_le = "f2eee8d8f6e5fb182c610cae6c5393fce69dc4d900e7b4923b074e54ad00fb36";
+        let fe_be = "36fb00ad544e073b92b4e700d9c49de6fc93536cae0c612c18fbe5f6d8e8eef2";
+        let fe = BaseField::from_hex(fe_le).expect("Failed to deserialize field hex");
+        assert_eq!(fe.to_hex_with_endianness(Endianness::Little), fe_le);
+        assert_eq!(fe.to_hex_with_endianness(Endianness::Big), fe_be);
+        assert_eq!(
+            BaseField::from_hex_with_endianness(fe_be, Endianness::Big),
+            Ok(fe)
+        );
+
+        // the big-endian encoding of this element is out of range when read as little-endian
+        assert_eq!(
+            BaseField::from_hex_with_endianness(fe_le, Endianness::Big),
+            Err(FieldHelpersError::DeserializeBytes)
+        );
+    }
+
     #[test]
     fn field_bytes() {
         assert_eq!(
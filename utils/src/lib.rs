@@ -5,6 +5,7 @@ pub mod evaluations;
 pub mod field_helpers;
 pub mod hasher;
 pub mod math;
+pub mod parallelism;
 pub mod serialization;
 
 pub use dense_polynomial::ExtendedDensePolynomial;
@@ -0,0 +1,69 @@
+//! This module decides when the helpers of this crate should use rayon.
+//! Spawning rayon tasks has a cost that dominates the actual work
+//! on small inputs (for example the many small polynomials of leaf circuits),
+//! so inputs below a threshold are processed serially.
+
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+
+/// Number of elements below which loops run serially.
+/// See the `parallelism` benchmark of this crate for the crossover point.
+pub const DEFAULT_RAYON_THRESHOLD: usize = 1 << 10;
+
+/// Environment variable that overrides [DEFAULT_RAYON_THRESHOLD].
+pub const RAYON_THRESHOLD_ENV: &str = "O1_RAYON_THRESHOLD";
+
+static RAYON_THRESHOLD: Lazy<usize> = Lazy::new(|| {
+    std::env::var(RAYON_THRESHOLD_ENV)
+        .ok()
+        .and_then(|t| t.parse().ok())
+        .unwrap_or(DEFAULT_RAYON_THRESHOLD)
+});
+
+/// Returns the threshold below which loops run serially.
+/// It is read once from the `O1_RAYON_THRESHOLD` environment variable,
+/// and defaults to [DEFAULT_RAYON_THRESHOLD].
+pub fn rayon_threshold() -> usize {
+    *RAYON_THRESHOLD
+}
+
+/// Applies `f` to every element of `xs`, in parallel if `xs` has at least [rayon_threshold] elements.
+pub fn for_each_mut<T, F>(xs: &mut [T], f: F)
+where
+    T: Send,
+    F: Fn(&mut T) + Send + Sync,
+{
+    for_each_mut_with_threshold(xs, rayon_threshold(), f)
+}
+
+/// Applies `f` to every element of `xs`, in parallel if `xs` has at least `threshold` elements.
+pub fn for_each_mut_with_threshold<T, F>(xs: &mut [T], threshold: usize, f: F)
+where
+    T: Send,
+    F: Fn(&mut T) + Send + Sync,
+{
+    if xs.len() < threshold {
+        xs.iter_mut().for_each(f);
+    } else {
+        xs.par_iter_mut().for_each(f);
+    }
+}
+
+//
+// Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serial_and_parallel_agree() {
+        let xs: Vec<u64> = (0..100).collect();
+        let mut serial = xs.clone();
+        let mut parallel = xs;
+        for_each_mut_with_threshold(&mut serial, usize::MAX, |x| *x = *x * *x + 1);
+        for_each_mut_with_threshold(&mut parallel, 0, |x| *x = *x * *x + 1);
+        assert_eq!(serial, parallel);
+    }
+}